@@ -0,0 +1,55 @@
+use crate::{
+    commit::Commit,
+    diagnostic::{Diagnostic, Severity},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnalysisResult {
+    pub commit: Commit,
+    pub diagnostics: Vec<Diagnostic>,
+    pub skipped: Option<String>,
+    pub category: Option<String>,
+    /// The ids of the rules that were checked, whether they passed or not.
+    pub checked: Vec<&'static str>,
+}
+
+impl AnalysisResult {
+    pub fn errors(&self) -> Vec<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .collect()
+    }
+
+    pub fn valid(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    /// Returns every checked rule together with its first diagnostic, or
+    /// none if it passed.
+    pub fn outcomes(&self) -> Vec<(&'static str, Option<&Diagnostic>)> {
+        let mut rules = self.checked.clone();
+        for diagnostic in &self.diagnostics {
+            if !rules.contains(&diagnostic.code) {
+                rules.push(diagnostic.code);
+            }
+        }
+        rules
+            .into_iter()
+            .map(|rule| {
+                let found = self
+                    .diagnostics
+                    .iter()
+                    .find(|diagnostic| diagnostic.code == rule);
+                (rule, found)
+            })
+            .collect()
+    }
+
+    pub fn status(&self) -> String {
+        match &self.skipped {
+            Some(reason) => reason.to_string(),
+            None => self.valid().to_string(),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::footer::{self, is_breaking_token};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Commit {
+    pub commit_type: String,
+    pub scope: String,
+    pub description: String,
+    pub body: String,
+    pub footers: Vec<(String, String)>,
+    pub breaking: bool,
+}
+
+impl Commit {
+    /// The descriptions of the breaking changes, from the `BREAKING CHANGE`
+    /// footers, or the description of the header if it is only marked with
+    /// `!`.
+    pub fn breaking_changes(&self) -> Vec<String> {
+        let footers: Vec<String> = self
+            .footers
+            .iter()
+            .filter(|(token, value)| is_breaking_token(token) && !value.trim().is_empty())
+            .map(|(_, value)| value.to_string())
+            .collect();
+        match (self.breaking, footers.is_empty()) {
+            (true, true) => vec![self.description.to_string()],
+            _ => footers,
+        }
+    }
+
+    // A commit is breaking if the header is marked with `!`, or if it has a
+    // `BREAKING CHANGE` footer.
+    pub(crate) fn new(header: (String, String, String, bool), body: &str) -> Commit {
+        let body = body.trim().to_string();
+        let footers = footer::parse(&body);
+        Commit {
+            commit_type: header.0,
+            scope: header.1,
+            description: header.2,
+            breaking: header.3 || footers.iter().any(|(token, _)| is_breaking_token(token)),
+            footers,
+            body,
+        }
+    }
+}
+
+#[test]
+fn test_breaking_changes() {
+    let header = |breaking| {
+        (
+            "feat".to_string(),
+            "api".to_string(),
+            "drop v1".to_string(),
+            breaking,
+        )
+    };
+    assert!(Commit::new(header(false), "").breaking_changes().is_empty());
+    assert_eq!(
+        Commit::new(header(true), "").breaking_changes(),
+        vec!["drop v1"]
+    );
+    assert_eq!(
+        Commit::new(
+            header(true),
+            "Some body\n\nBREAKING CHANGE: v1 is gone\nBREAKING-CHANGE: so is v2"
+        )
+        .breaking_changes(),
+        vec!["v1 is gone", "so is v2"]
+    );
+}
@@ -0,0 +1,220 @@
+use std::io::{Error, ErrorKind};
+
+use crate::{
+    config::{Config, SubjectCase},
+    header::CommitMessage,
+    json::{self, Value},
+};
+
+fn unsupported(rule: &str, value: &Value) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Unsupported commitlint setting for '{}': {}", rule, value),
+    )
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// commitlint rules are `[level, "always" | "never", value]`, where level 0
+// disables the rule. Levels 1 and 2 both enable it with rcop's severity.
+fn condition<'a>(
+    rule: &str,
+    setting: &'a Value,
+) -> Result<Option<(&'a str, Option<&'a Value>)>, Error> {
+    let values = setting
+        .as_array()
+        .ok_or_else(|| unsupported(rule, setting))?;
+    let level = values
+        .first()
+        .and_then(Value::as_f64)
+        .ok_or_else(|| unsupported(rule, setting))?;
+    if level == 0.0 {
+        return Ok(None);
+    }
+    let applicable = values.get(1).and_then(Value::as_str).unwrap_or("always");
+    Ok(Some((applicable, values.get(2))))
+}
+
+fn subject_case(never: bool, cases: &[String]) -> Option<SubjectCase> {
+    let has = |case: &str| cases.iter().any(|existing| existing == case);
+    match (never, cases) {
+        (false, [case]) if case == "lower-case" => Some(SubjectCase::Lower),
+        (false, [case]) if case == "sentence-case" => Some(SubjectCase::Sentence),
+        (false, [case]) if case == "upper-case" => Some(SubjectCase::Upper),
+        (true, _) if has("lower-case") => Some(SubjectCase::Sentence),
+        (true, _) if has("sentence-case") || has("upper-case") || has("pascal-case") => {
+            Some(SubjectCase::Lower)
+        }
+        _ => None,
+    }
+}
+
+/// Maps the supported subset of a commitlint JSON config (`type-enum`,
+/// `scope-empty`, `subject-max-length` and `subject-case`) onto a `Config`.
+/// Other rules are ignored.
+pub fn parse(text: &str) -> Result<Config, Error> {
+    let mut config = Config::default();
+    let root = json::parse(text)?;
+    let rules = match root.get("rules") {
+        Some(rules) => rules,
+        None => return Ok(config),
+    };
+
+    if let Some(setting) = rules.get("type-enum") {
+        if let Some((applicable, value)) = condition("type-enum", setting)? {
+            // rcop has no list of types that are not allowed.
+            if applicable != "always" {
+                return Err(unsupported("type-enum", setting));
+            }
+            config.commit_types = strings(value)
+                .into_iter()
+                .map(|commit_type| CommitMessage {
+                    commit_type,
+                    required: vec!["description".to_string()],
+                    ..Default::default()
+                })
+                .collect();
+        }
+    }
+    if let Some(setting) = rules.get("scope-empty") {
+        match condition("scope-empty", setting)? {
+            Some(("never", _)) => {
+                for commit_type in config.commit_types.iter_mut() {
+                    if !commit_type.required.contains(&"scope".to_string()) {
+                        commit_type.required.insert(0, "scope".to_string());
+                    }
+                }
+            }
+            Some(("always", _)) => {
+                for commit_type in config.commit_types.iter_mut() {
+                    commit_type.required.retain(|field| field != "scope");
+                    commit_type.forbidden.push("scope".to_string());
+                }
+            }
+            Some(_) => return Err(unsupported("scope-empty", setting)),
+            None => {}
+        }
+    }
+    if let Some(setting) = rules.get("subject-max-length") {
+        if let Some((applicable, value)) = condition("subject-max-length", setting)? {
+            let max = value
+                .and_then(Value::as_f64)
+                .filter(|max| applicable == "always" && *max >= 0.0 && max.fract() == 0.0)
+                .ok_or_else(|| unsupported("subject-max-length", setting))?;
+            config.max_description_length = Some(max as usize);
+        }
+    }
+    if let Some(setting) = rules.get("subject-case") {
+        if let Some((applicable, value)) = condition("subject-case", setting)? {
+            let cases = match value {
+                Some(Value::String(case)) => vec![case.to_string()],
+                value => strings(value),
+            };
+            config.subject_case = subject_case(applicable == "never", &cases)
+                .ok_or_else(|| unsupported("subject-case", setting))?;
+        }
+    }
+    Ok(config)
+}
+
+#[test]
+fn test_parse_commitlint_config() {
+    let text = r#"{
+        "extends": ["@commitlint/config-conventional"],
+        "rules": {
+            "type-enum": [2, "always", ["feat", "fix", "docs"]],
+            "scope-empty": [2, "never"],
+            "subject-max-length": [1, "always", 72],
+            "subject-case": [2, "never", ["sentence-case", "start-case", "pascal-case", "upper-case"]],
+            "body-leading-blank": [1, "always"]
+        }
+    }"#;
+    let commit_type = |name: &str| CommitMessage {
+        commit_type: name.to_string(),
+        required: vec!["scope".to_string(), "description".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        parse(text).unwrap(),
+        Config {
+            commit_types: vec![commit_type("feat"), commit_type("fix"), commit_type("docs")],
+            max_description_length: Some(72),
+            subject_case: SubjectCase::Lower,
+            ..Config::default()
+        }
+    );
+}
+
+#[test]
+fn test_parse_commitlint_config_disabled_rules() {
+    let text = r#"{"rules": {"type-enum": [0, "always", ["wip"]], "subject-case": [2, "always", "upper-case"]}}"#;
+    assert_eq!(
+        parse(text).unwrap(),
+        Config {
+            subject_case: SubjectCase::Upper,
+            ..Config::default()
+        }
+    );
+    assert_eq!(parse("{}").unwrap(), Config::default());
+}
+
+#[test]
+fn test_parse_commitlint_config_scope_empty_always() {
+    let text =
+        r#"{"rules": {"type-enum": [2, "always", ["chore"]], "scope-empty": [2, "always"]}}"#;
+    assert_eq!(
+        parse(text).unwrap().commit_types,
+        vec![CommitMessage {
+            commit_type: "chore".to_string(),
+            required: vec!["description".to_string()],
+            forbidden: vec!["scope".to_string()],
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn test_parse_commitlint_config_unsupported() {
+    for text in [
+        r#"{"rules": {"scope-empty": [2, "sometimes"]}}"#,
+        r#"{"rules": {"subject-case": [2, "always", "camel-case"]}}"#,
+        r#"{"rules": {"subject-max-length": [2, "always", "long"]}}"#,
+        r#"{"rules": {"subject-max-length": [2, "always", -5]}}"#,
+        r#"{"rules": {"subject-max-length": [2, "always", 72.5]}}"#,
+        r#"{"rules": {"type-enum": "feat"}}"#,
+    ] {
+        assert_eq!(
+            parse(text).unwrap_err().kind(),
+            ErrorKind::InvalidData,
+            "Should have failed for '{}'",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_parse_commitlint_config_never() {
+    assert_eq!(
+        parse(r#"{"rules": {"type-enum": [2, "never", ["wip"]]}}"#)
+            .unwrap_err()
+            .to_string(),
+        r#"Unsupported commitlint setting for 'type-enum': [2,"never",["wip"]]"#
+    );
+    assert_eq!(
+        parse(r#"{"rules": {"subject-max-length": [2, "never", 72]}}"#)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
@@ -0,0 +1,450 @@
+use std::io::{Error, ErrorKind};
+
+use regex::Regex;
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    header::{default_commit_types, CommitMessage, ParseOptions},
+};
+
+// A compiled regex that compares by its source, so configs stay comparable.
+#[derive(Clone, Debug)]
+pub struct Pattern(pub Regex);
+
+impl Pattern {
+    pub fn new(text: &str) -> Result<Pattern, Error> {
+        Regex::new(text).map(Pattern).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid pattern '{}': {}", text, e),
+            )
+        })
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScopeCase {
+    #[default]
+    Any,
+    Lower,
+    Kebab,
+}
+
+pub fn parse_scope_case(text: &str) -> Result<ScopeCase, Error> {
+    match text {
+        "any" => Ok(ScopeCase::Any),
+        "lower" => Ok(ScopeCase::Lower),
+        "kebab" => Ok(ScopeCase::Kebab),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown scope case '{}', expected one of: any, lower, kebab",
+                text
+            ),
+        )),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SubjectCase {
+    #[default]
+    Any,
+    Lower,
+    Sentence,
+    Upper,
+}
+
+pub fn parse_subject_case(text: &str) -> Result<SubjectCase, Error> {
+    match text {
+        "any" => Ok(SubjectCase::Any),
+        "lower" => Ok(SubjectCase::Lower),
+        "sentence" => Ok(SubjectCase::Sentence),
+        "upper" => Ok(SubjectCase::Upper),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown subject case '{}', expected one of: any, lower, sentence, upper",
+                text
+            ),
+        )),
+    }
+}
+
+/// What to do about a header with empty parentheses instead of a scope,
+/// like `feat(): x`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EmptyScopeParens {
+    #[default]
+    Error,
+    Warn,
+    Accept,
+}
+
+pub fn parse_empty_scope_parens(text: &str) -> Result<EmptyScopeParens, Error> {
+    match text {
+        "error" => Ok(EmptyScopeParens::Error),
+        "warn" => Ok(EmptyScopeParens::Warn),
+        "accept" => Ok(EmptyScopeParens::Accept),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown empty scope parens mode '{}', expected one of: error, warn, accept",
+                text
+            ),
+        )),
+    }
+}
+
+/// Parses the type and scope of release commits, like `chore(release)`. The
+/// scope can be left out to treat every commit of the type as a release.
+pub fn parse_release_commit(text: &str) -> Result<(String, String), Error> {
+    let (commit_type, scope) = match text.trim().split_once('(') {
+        Some((commit_type, rest)) => match rest.strip_suffix(')') {
+            Some(scope) => (commit_type, scope),
+            None => ("", ""),
+        },
+        None => (text.trim(), ""),
+    };
+    if commit_type.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Invalid release commit '{}', expected TYPE or TYPE(SCOPE), e.g. chore(release)",
+                text
+            ),
+        ));
+    }
+    Ok((commit_type.to_string(), scope.trim().to_string()))
+}
+
+/// How footers that close or reference issues write the issue, e.g.
+/// `Closes #12`, `Closes: #12` or `Refs: JIRA-3`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IssueFooterStyle {
+    Hash,
+    ColonHash,
+    Ticket,
+}
+
+pub fn parse_issue_footer_style(text: &str) -> Result<IssueFooterStyle, Error> {
+    match text {
+        "hash" => Ok(IssueFooterStyle::Hash),
+        "colon-hash" => Ok(IssueFooterStyle::ColonHash),
+        "ticket" => Ok(IssueFooterStyle::Ticket),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown issue footer style '{}', expected one of: hash, colon-hash, ticket",
+                text
+            ),
+        )),
+    }
+}
+
+/// Parses `trailing-period=error,imperative-mood=off` into the rules and
+/// their severity, where `off` is `None`.
+pub fn parse_severity_overrides(text: &str) -> Result<Vec<(String, Option<Severity>)>, Error> {
+    let mut overrides = vec![];
+    for entry in text.split(',').map(|entry| entry.trim()) {
+        if entry.is_empty() {
+            continue;
+        }
+        let severity = match entry.split_once('=') {
+            Some((rule, "error")) => (rule, Some(Severity::Error)),
+            Some((rule, "warning")) => (rule, Some(Severity::Warning)),
+            Some((rule, "off")) => (rule, None),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                    "Invalid severity override '{}', expected rule=error, rule=warning or rule=off",
+                    entry
+                ),
+                ))
+            }
+        };
+        overrides.push((severity.0.trim().to_string(), severity.1));
+    }
+    Ok(overrides)
+}
+
+/// Parses a file with one entry per line, like the scopes of
+/// `--scopes-file`. Blank lines and lines starting with `#` are skipped.
+pub fn parse_list_file(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Derives scopes from the path patterns of a CODEOWNERS file. The scope of
+/// a pattern is its last directory before any wildcard, so `/src/api/` and
+/// `packages/ui/**` give `api` and `ui`. Patterns without a directory, like
+/// `*.js` or `/Cargo.toml`, give none.
+pub fn parse_codeowners(text: &str) -> Vec<String> {
+    let mut scopes: Vec<String> = vec![];
+    for line in parse_list_file(text) {
+        let pattern = line.split_whitespace().next().unwrap_or("");
+        let mut segments: Vec<&str> = pattern
+            .split('/')
+            .take_while(|segment| !segment.contains(['*', '?', '[']))
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let is_directory = pattern.ends_with('/') || pattern.contains(['*', '?', '[']);
+        if !is_directory && segments.last().is_some_and(|name| name.contains('.')) {
+            segments.pop();
+        }
+        if let Some(scope) = segments.last() {
+            if !scopes.iter().any(|existing| existing == scope) {
+                scopes.push(scope.to_string());
+            }
+        }
+    }
+    scopes
+}
+
+/// Placeholders that templates leave in the header, like `<scope>` or `XXX`.
+/// Angle brackets right after a word are generics like `Vec<u8>` instead.
+pub const PLACEHOLDER_PATTERNS: &[&str] = &[r"\B<[\w -]+>", r"\bXXX\b"];
+
+/// The endings of a first word that suggest it isn't in the imperative mood,
+/// like `added`, `adding` or `adds`.
+pub const NON_IMPERATIVE_SUFFIXES: &[&str] = &["ed", "ing", "s"];
+
+/// Imperative verbs that end like a non imperative one.
+pub const IMPERATIVE_EXCEPTIONS: &[&str] = &[
+    "access", "address", "bring", "bypass", "embed", "feed", "focus", "need", "pass", "process",
+    "proceed", "seed", "speed", "string",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub commit_types: Vec<CommitMessage>,
+    pub ignore_case: bool,
+    pub parse_options: ParseOptions,
+    pub disabled_rules: Vec<String>,
+    pub severity_overrides: Vec<(String, Option<Severity>)>,
+    pub squash_aware: bool,
+    pub only_types: Vec<String>,
+    pub breaking_allowed_for: Option<Vec<String>>,
+    pub capitalized_footer_tokens: bool,
+    pub footer_blank_line: bool,
+    pub warn_url_only_description: bool,
+    pub warn_trailing_issue_ref: bool,
+    pub warn_trailing_ellipsis: bool,
+    pub warn_redundant_type: bool,
+    pub warn_mixed_scope_separators: bool,
+    pub fuzzy_type: bool,
+    pub warn_non_imperative: bool,
+    pub non_imperative_suffixes: Vec<String>,
+    pub imperative_exceptions: Vec<String>,
+    pub body_mentions_scope: bool,
+    pub require_revert_sha: bool,
+    pub require_release_version: bool,
+    pub release_commit: (String, String),
+    pub ascii_only_subject: bool,
+    pub encoding_check: bool,
+    pub strict_separator_spacing: bool,
+    pub description_pattern: Option<Pattern>,
+    pub prefix_pattern: Option<Pattern>,
+    pub placeholder_patterns: Vec<Pattern>,
+    pub ignore_patterns: Vec<Pattern>,
+    pub scope_case: ScopeCase,
+    pub empty_scope_parens: EmptyScopeParens,
+    pub lowercase_scope_for: Vec<String>,
+    pub sort_scopes: bool,
+    pub subject_case: SubjectCase,
+    pub max_description_length: Option<usize>,
+    pub max_message_bytes: Option<usize>,
+    pub max_consecutive_blank_lines: Option<usize>,
+    pub max_body_lines: Option<usize>,
+    pub length_excludes_ticket: bool,
+    pub scope_types: Vec<(String, Vec<String>)>,
+    pub deny_scopes: Vec<String>,
+    pub allowed_scopes: Vec<String>,
+    pub reviewers: Vec<String>,
+    pub branch_ticket: Option<String>,
+    pub allowed_footers: Vec<String>,
+    pub issue_footer_style: Option<IssueFooterStyle>,
+    pub forbidden_body_words: Vec<String>,
+    pub required_body_sections: Vec<String>,
+    pub allowed_verbs: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            commit_types: default_commit_types(),
+            ignore_case: false,
+            parse_options: ParseOptions::default(),
+            disabled_rules: vec![],
+            severity_overrides: vec![],
+            squash_aware: false,
+            only_types: vec![],
+            breaking_allowed_for: None,
+            capitalized_footer_tokens: false,
+            footer_blank_line: false,
+            warn_url_only_description: false,
+            warn_trailing_issue_ref: false,
+            warn_trailing_ellipsis: false,
+            warn_redundant_type: false,
+            warn_mixed_scope_separators: false,
+            fuzzy_type: false,
+            warn_non_imperative: false,
+            non_imperative_suffixes: NON_IMPERATIVE_SUFFIXES
+                .iter()
+                .map(|suffix| suffix.to_string())
+                .collect(),
+            imperative_exceptions: IMPERATIVE_EXCEPTIONS
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+            body_mentions_scope: false,
+            require_revert_sha: false,
+            require_release_version: false,
+            release_commit: ("chore".to_string(), "release".to_string()),
+            ascii_only_subject: false,
+            encoding_check: false,
+            strict_separator_spacing: false,
+            description_pattern: None,
+            prefix_pattern: None,
+            placeholder_patterns: PLACEHOLDER_PATTERNS
+                .iter()
+                .map(|pattern| Pattern::new(pattern).unwrap())
+                .collect(),
+            ignore_patterns: vec![],
+            scope_case: ScopeCase::Any,
+            empty_scope_parens: EmptyScopeParens::Error,
+            lowercase_scope_for: vec![],
+            sort_scopes: false,
+            subject_case: SubjectCase::Any,
+            max_description_length: None,
+            max_message_bytes: None,
+            max_consecutive_blank_lines: None,
+            max_body_lines: None,
+            length_excludes_ticket: false,
+            scope_types: vec![],
+            deny_scopes: vec![],
+            allowed_scopes: vec![],
+            reviewers: vec![],
+            branch_ticket: None,
+            allowed_footers: vec![],
+            issue_footer_style: None,
+            forbidden_body_words: vec![],
+            required_body_sections: vec![],
+            allowed_verbs: vec![],
+        }
+    }
+}
+
+impl Config {
+    pub fn is_disabled(&self, rule: &str) -> bool {
+        self.disabled_rules.iter().any(|disabled| disabled == rule)
+            || self
+                .severity_overrides
+                .iter()
+                .any(|(overridden, severity)| overridden == rule && severity.is_none())
+    }
+
+    /// Drops the diagnostics of disabled rules and applies the severity
+    /// overrides to the others.
+    pub fn apply_severities(&self, diagnostics: &mut Vec<Diagnostic>) {
+        diagnostics.retain(|diagnostic| !self.is_disabled(diagnostic.code));
+        for diagnostic in diagnostics.iter_mut() {
+            if let Some((_, Some(severity))) = self
+                .severity_overrides
+                .iter()
+                .rev()
+                .find(|(rule, _)| rule == diagnostic.code)
+            {
+                diagnostic.severity = *severity;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_codeowners() {
+    let text = "# Everything else\n*       @org/maintainers\n*.js    @org/frontend\n/Cargo.toml @org/maintainers\n/src/api/ @org/backend\npackages/ui/** @org/frontend\ndocs @org/writers\n/src/api/v2/ @org/backend\n/src/cli/*.rs @org/backend\n";
+    assert_eq!(
+        parse_codeowners(text),
+        vec!["api", "ui", "docs", "v2", "cli"]
+    );
+    assert!(parse_codeowners("").is_empty());
+}
+
+#[test]
+fn test_parse_release_commit() {
+    assert_eq!(
+        parse_release_commit("chore(release)").unwrap(),
+        ("chore".to_string(), "release".to_string())
+    );
+    assert_eq!(
+        parse_release_commit("release").unwrap(),
+        ("release".to_string(), "".to_string())
+    );
+    for text in ["", "(release)", "chore(release"] {
+        assert!(parse_release_commit(text).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn test_parse_list_file() {
+    assert_eq!(
+        parse_list_file("# generated from the crates\napi\n\n  ui  \ncli\n"),
+        vec!["api", "ui", "cli"]
+    );
+    assert!(parse_list_file("").is_empty());
+}
+
+#[test]
+fn test_parse_severity_overrides() {
+    assert_eq!(
+        parse_severity_overrides(
+            "trailing-period=error, imperative-mood=off,scope-required=warning"
+        )
+        .unwrap(),
+        vec![
+            ("trailing-period".to_string(), Some(Severity::Error)),
+            ("imperative-mood".to_string(), None),
+            ("scope-required".to_string(), Some(Severity::Warning)),
+        ]
+    );
+    assert!(parse_severity_overrides("").unwrap().is_empty());
+    assert_eq!(
+        parse_severity_overrides("trailing-period=fatal")
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert!(parse_severity_overrides("trailing-period").is_err());
+}
+
+#[test]
+fn test_apply_severities() {
+    let config = Config {
+        severity_overrides: parse_severity_overrides("trailing-period=error,scope-required=off")
+            .unwrap(),
+        ..Config::default()
+    };
+    let mut diagnostics = vec![
+        Diagnostic::warning("trailing-period", "period"),
+        Diagnostic::error("scope-required", "scope"),
+        Diagnostic::warning("description-case", "case"),
+    ];
+    config.apply_severities(&mut diagnostics);
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::error("trailing-period", "period"),
+            Diagnostic::warning("description-case", "case"),
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The name of the rule that reported it, e.g. `type-enum`.
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// The byte range of the message the diagnostic is about, if a check
+    /// can tell, e.g. for editors to underline it.
+    pub span: Option<(usize, usize)>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: &str) -> Diagnostic {
+        Diagnostic {
+            code,
+            severity: Severity::Error,
+            message: message.to_string(),
+            span: None,
+        }
+    }
+
+    pub fn warning(code: &'static str, message: &str) -> Diagnostic {
+        Diagnostic {
+            code,
+            severity: Severity::Warning,
+            message: message.to_string(),
+            span: None,
+        }
+    }
+
+    pub fn with_span(self, start: usize, end: usize) -> Diagnostic {
+        Diagnostic {
+            span: Some((start, end)),
+            ..self
+        }
+    }
+
+    // Checks of a single line give spans within it, which are moved to the
+    // position of the line in the message.
+    pub(crate) fn offset(self, by: usize) -> Diagnostic {
+        Diagnostic {
+            span: self.span.map(|(start, end)| (start + by, end + by)),
+            ..self
+        }
+    }
+}
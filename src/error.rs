@@ -0,0 +1,97 @@
+use std::fmt;
+
+/// The reasons a commit header doesn't match the commit types spec.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// The type, and the configured types that have a help text, with it.
+    UnknownType(String, Vec<(String, String)>),
+    MissingScope,
+    MissingDescription,
+    DescriptionTooShort(usize),
+    MissingBody,
+    UnexpectedScope(String),
+    /// The scope, the commit type, and the scopes allowed for it.
+    UnknownScope(String, String, Vec<String>),
+    UnlistedScope(String),
+    ForbiddenScope(String),
+    BreakingNotAllowed(String),
+    UnfilledPlaceholder(String),
+}
+
+impl ValidationError {
+    /// The id of the rule that reports this error.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            ValidationError::UnknownType(_, _) => "type-enum",
+            ValidationError::MissingScope => "scope-required",
+            ValidationError::MissingDescription => "description-required",
+            ValidationError::DescriptionTooShort(_) => "description-min-length",
+            ValidationError::MissingBody => "body-required",
+            ValidationError::UnexpectedScope(_) => "scope-forbidden",
+            ValidationError::UnknownScope(_, _, _) => "scope-enum",
+            ValidationError::UnlistedScope(_) => "scope-enum",
+            ValidationError::ForbiddenScope(_) => "scope-denied",
+            ValidationError::BreakingNotAllowed(_) => "breaking-not-allowed",
+            ValidationError::UnfilledPlaceholder(_) => "header-placeholder",
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UnknownType(_, help) => {
+                write!(f, "Commit type not allowed")?;
+                if !help.is_empty() {
+                    write!(f, ", expected one of:")?;
+                }
+                for (commit_type, text) in help {
+                    write!(f, "\n  {}: {}", commit_type, text)?;
+                }
+                Ok(())
+            }
+            ValidationError::MissingScope => {
+                write!(f, "Commit type requires a scope, but none given")
+            }
+            ValidationError::MissingDescription => {
+                write!(f, "Commit type requires a description, but none given")
+            }
+            ValidationError::DescriptionTooShort(min) => write!(
+                f,
+                "Commit type requires a description of at least {} characters",
+                min
+            ),
+            ValidationError::MissingBody => {
+                write!(f, "Commit type requires a body, but none given")
+            }
+            ValidationError::UnexpectedScope(scope) => {
+                write!(f, "Commit type doesn't allow a scope, but got '{}'", scope)
+            }
+            ValidationError::UnknownScope(scope, commit_type, allowed) => write!(
+                f,
+                "Scope '{}' is not allowed for the commit type '{}', expected one of: {}",
+                scope,
+                commit_type,
+                allowed.join(", ")
+            ),
+            ValidationError::UnlistedScope(scope) => {
+                write!(f, "Scope '{}' is not in the list of allowed scopes", scope)
+            }
+            ValidationError::ForbiddenScope(scope) => {
+                write!(f, "Scope '{}' is not allowed", scope)
+            }
+            ValidationError::BreakingNotAllowed(commit_type) => write!(
+                f,
+                "Commit type '{}' doesn't allow breaking changes",
+                commit_type
+            ),
+            ValidationError::UnfilledPlaceholder(placeholder) => write!(
+                f,
+                "Header still contains the placeholder '{}' of a template",
+                placeholder
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
@@ -0,0 +1,204 @@
+use crate::{commit::Commit, config::Config, footer::is_breaking_token, header, rules};
+
+/// Proposes a corrected version of a header: the type in the spelling of the
+/// configured types, or the type a misspelled one like `fet` is closest to,
+/// a placeholder for a required scope that is missing,
+/// a list of scopes without duplicates, sorted with `--sort-scopes`, and the
+/// description without a trailing period. Returns `None` if there
+/// is nothing to correct, or if the header can't be parsed at all.
+pub fn suggest_header(line: &str, config: &Config) -> Option<String> {
+    let line = line.trim();
+    let (commit_type, mut scope, description, breaking) =
+        header::parse_with(line, config.parse_options)
+            .ok()
+            .or_else(|| {
+                header::suggest_swap(line, &config.commit_types, config.ignore_case)
+                    .and_then(|swapped| header::parse_with(&swapped, config.parse_options).ok())
+            })?;
+    let commit_type = header::find(&config.commit_types, true, &commit_type)
+        .or_else(|| header::find_misspelled(&config.commit_types, true, &commit_type))?;
+    if commit_type.required.contains(&"scope".to_string()) && scope.is_empty() {
+        scope = "scope".to_string();
+    }
+    let mut list = rules::scope_list(&scope);
+    if config.sort_scopes {
+        list.sort();
+    }
+    let mut unique: Vec<&str> = vec![];
+    for entry in list {
+        if !unique.contains(&entry) {
+            unique.push(entry);
+        }
+    }
+    if unique.len() > 1 {
+        scope = unique.join(",");
+    }
+    let description = match description.strip_suffix('.') {
+        Some(stripped) if !stripped.ends_with('.') => stripped.trim_end(),
+        _ => description.as_str(),
+    };
+
+    let suggestion = format_header(&commit_type.commit_type, &scope, breaking, description);
+    if suggestion == line {
+        return None;
+    }
+    Some(suggestion)
+}
+
+fn format_header(commit_type: &str, scope: &str, breaking: bool, description: &str) -> String {
+    let mut header = commit_type.to_string();
+    if !scope.is_empty() {
+        header.push_str(&format!("({})", scope));
+    }
+    if breaking {
+        header.push('!');
+    }
+    header.push_str(&format!(": {}", description));
+    header
+}
+
+/// Reconstructs the message of a commit in its canonical form: the header in
+/// the spelling of the configured types and with a single space after the
+/// colon, and the body without trailing whitespace or repeated blank lines.
+/// A breaking change is only marked with `!` if no footer already says so.
+pub fn normalize(commit: &Commit, config: &Config) -> String {
+    let commit_type = header::find(
+        &config.commit_types,
+        config.ignore_case,
+        &commit.commit_type,
+    )
+    .map_or(commit.commit_type.as_str(), |found| {
+        found.commit_type.as_str()
+    });
+    let breaking = commit.breaking
+        && !commit
+            .footers
+            .iter()
+            .any(|(token, _)| is_breaking_token(token));
+    let mut message = format_header(commit_type, &commit.scope, breaking, &commit.description);
+    message.push('\n');
+
+    let mut previous_blank = true;
+    for line in commit.body.lines().map(str::trim_end) {
+        if line.is_empty() && previous_blank {
+            continue;
+        }
+        if previous_blank {
+            message.push('\n');
+        }
+        previous_blank = line.is_empty();
+        if !previous_blank {
+            message.push_str(line);
+            message.push('\n');
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+use crate::header::CommitMessage;
+
+#[test]
+fn test_suggest_header() {
+    let config = Config {
+        commit_types: vec![
+            CommitMessage {
+                commit_type: "fix".to_string(),
+                required: vec!["description".to_string()],
+                ..Default::default()
+            },
+            CommitMessage {
+                commit_type: "feat".to_string(),
+                required: vec!["scope".to_string(), "description".to_string()],
+                ..Default::default()
+            },
+        ],
+        ..Config::default()
+    };
+    let test_cases = vec![
+        ("Fix: message.", Some("fix: message")),
+        ("FIX!:message", Some("fix!: message")),
+        ("feat: add endpoint", Some("feat(scope): add endpoint")),
+        ("(api)feat: add endpoint", Some("feat(api): add endpoint")),
+        ("fix: wait for it...", None),
+        ("fix: message", None),
+        ("feat(api): add endpoint", None),
+        ("fet(api): add endpoint", Some("feat(api): add endpoint")),
+        ("fx: message", Some("fix: message")),
+        ("wip: message.", None),
+        ("not a header", None),
+        ("fix(api,ui,api): message", Some("fix(api,ui): message")),
+        ("fix(ui,api): message", None),
+    ];
+    for (line, expected) in test_cases {
+        assert_eq!(
+            suggest_header(line, &config).as_deref(),
+            expected,
+            "Unexpected suggestion for '{}'",
+            line
+        );
+    }
+}
+
+#[test]
+fn test_suggest_sorted_scopes() {
+    let config = Config {
+        sort_scopes: true,
+        ..Config::default()
+    };
+    assert_eq!(
+        suggest_header("fix(ui,api,ui): message", &config).as_deref(),
+        Some("fix(api,ui): message")
+    );
+    assert_eq!(suggest_header("fix(api,ui): message", &config), None);
+}
+
+#[test]
+fn test_normalize() {
+    let config = Config {
+        ignore_case: true,
+        ..Config::default()
+    };
+    let commit = crate::parse(std::io::Cursor::new(
+        "DOCS!:   fix the readme   \n\n\nFirst paragraph  \nstill first\n\n\n\nSecond\t\n\n",
+    ))
+    .unwrap();
+    assert_eq!(
+        normalize(&commit, &config),
+        "docs!: fix the readme\n\nFirst paragraph\nstill first\n\nSecond\n"
+    );
+
+    let commit = crate::parse(std::io::Cursor::new(
+        "feat(api)!: drop v1\n\nBREAKING CHANGE: v1 is gone",
+    ))
+    .unwrap();
+    assert_eq!(
+        normalize(&commit, &config),
+        "feat(api): drop v1\n\nBREAKING CHANGE: v1 is gone\n"
+    );
+    let commit = crate::parse(std::io::Cursor::new("fix(ui): align")).unwrap();
+    assert_eq!(normalize(&commit, &config), "fix(ui): align\n");
+}
+
+#[test]
+fn test_normalize_breaking_round_trip() {
+    let config = Config::default();
+    for message in [
+        "feat!: x\n",
+        "feat(api)!: x\n",
+        "feat(api,ui)!: x\n\nA body.\n",
+    ] {
+        let commit = crate::parse(std::io::Cursor::new(message)).unwrap();
+        let normalized = normalize(&commit, &config);
+        assert_eq!(normalized, message);
+        assert_eq!(
+            crate::parse(std::io::Cursor::new(&normalized)).unwrap(),
+            commit,
+            "Round trip changed '{}'",
+            message
+        );
+    }
+    assert_eq!(format_header("feat", "", true, "x"), "feat!: x");
+    assert_eq!(format_header("feat", "api", true, "x"), "feat(api)!: x");
+    assert_eq!(format_header("feat", "", false, "x"), "feat: x");
+}
@@ -0,0 +1,79 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // https://www.conventionalcommits.org/en/v1.0.0/#specification: a footer is a
+    // word token followed by either `: ` or ` #`, `BREAKING CHANGE` being the
+    // only token allowed to contain a space.
+    static ref FOOTER: Regex =
+        Regex::new(r"^(BREAKING CHANGE|[A-Za-z0-9][A-Za-z0-9-]*)(?:: | #)(.*)$").unwrap();
+}
+
+pub(crate) fn is_breaking_token(token: &str) -> bool {
+    token == "BREAKING CHANGE" || token == "BREAKING-CHANGE"
+}
+
+pub(crate) fn parse_line(line: &str) -> Option<(String, String)> {
+    FOOTER.captures(line).map(|captures| {
+        let token = captures[1].to_string();
+        let separator = &line[token.len()..token.len() + 2];
+        let value = if separator == " #" {
+            format!("#{}", &captures[2])
+        } else {
+            captures[2].to_string()
+        };
+        (token, value)
+    })
+}
+
+// Footers are the last paragraph of the body, as long as every line in it is a
+// footer or the indented continuation of one.
+pub(crate) fn parse(body: &str) -> Vec<(String, String)> {
+    let paragraph = match body.trim_end().rsplit("\n\n").next() {
+        Some(paragraph) => paragraph,
+        None => return vec![],
+    };
+    let mut footers: Vec<(String, String)> = vec![];
+    for line in paragraph.lines() {
+        if let Some(footer) = parse_line(line) {
+            footers.push(footer);
+        } else if let (Some(last), true) =
+            (footers.last_mut(), line.starts_with(char::is_whitespace))
+        {
+            last.1.push('\n');
+            last.1.push_str(line.trim());
+        } else {
+            return vec![];
+        }
+    }
+    footers
+}
+
+#[test]
+fn test_parse_footers() {
+    let test_cases = vec![
+        ("", vec![]),
+        ("Just a body", vec![]),
+        ("Fixes the thing: for real", vec![]),
+        (
+            "Some body\n\nRefs: #123\nSigned-off-by: Jane Doe <jane@example.com>",
+            vec![
+                ("Refs", "#123"),
+                ("Signed-off-by", "Jane Doe <jane@example.com>"),
+            ],
+        ),
+        ("Closes #42", vec![("Closes", "#42")]),
+        (
+            "BREAKING CHANGE: the api changed\n  and this continues",
+            vec![("BREAKING CHANGE", "the api changed\nand this continues")],
+        ),
+        ("A body\nRefs: #123", vec![]),
+    ];
+    for (input, expected) in test_cases {
+        let expected: Vec<(String, String)> = expected
+            .iter()
+            .map(|(token, value)| (token.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(parse(input), expected, "Unexpected footers for '{}'", input);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    io::{Error, ErrorKind},
+    process::Command,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref TICKET: Regex = Regex::new(r"[A-Z][A-Z0-9]+-[0-9]+").unwrap();
+}
+
+/// Translates a duration like `7d` or `24h` into the relative date git
+/// understands for `--since`, e.g. `7 days ago`.
+pub(crate) fn parse_duration(text: &str) -> Result<String, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Invalid duration '{}', expected a number followed by one of: m, h, d, w",
+                text
+            ),
+        )
+    };
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = text.split_at(split);
+    let amount: usize = amount.parse().map_err(|_| invalid())?;
+    let unit = match unit {
+        "m" => "minutes",
+        "h" => "hours",
+        "d" => "days",
+        "w" => "weeks",
+        _ => return Err(invalid()),
+    };
+    Ok(format!("{} {} ago", amount, unit))
+}
+
+pub(crate) fn log_args(since: &str) -> Vec<String> {
+    vec![
+        "log".to_string(),
+        "-z".to_string(),
+        "--format=%B".to_string(),
+        format!("--since={}", since),
+    ]
+}
+
+pub(crate) fn previous_message_args() -> Vec<String> {
+    vec![
+        "log".to_string(),
+        "-1".to_string(),
+        "--format=%B".to_string(),
+    ]
+}
+
+pub(crate) fn git_dir_args() -> Vec<String> {
+    vec!["rev-parse".to_string(), "--git-dir".to_string()]
+}
+
+pub(crate) fn branch_args() -> Vec<String> {
+    vec![
+        "rev-parse".to_string(),
+        "--abbrev-ref".to_string(),
+        "HEAD".to_string(),
+    ]
+}
+
+/// Extracts a ticket like `API-123` from a branch name like `feature/API-123`.
+pub(crate) fn ticket(branch: &str) -> Option<String> {
+    TICKET
+        .find(branch.trim())
+        .map(|ticket| ticket.as_str().to_string())
+}
+
+/// Runs git with the given arguments and returns its standard output.
+pub(crate) fn run(args: &[String]) -> Result<String, Error> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("7d").unwrap(), "7 days ago");
+    assert_eq!(parse_duration("24h").unwrap(), "24 hours ago");
+    assert_eq!(parse_duration("30m").unwrap(), "30 minutes ago");
+    assert_eq!(parse_duration("2w").unwrap(), "2 weeks ago");
+    for text in ["", "7", "d", "7y", "-1d", "7 d", "1.5h"] {
+        assert_eq!(
+            parse_duration(text).unwrap_err().kind(),
+            ErrorKind::InvalidData,
+            "Should have failed for '{}'",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_log_args() {
+    assert_eq!(
+        log_args("7 days ago"),
+        vec!["log", "-z", "--format=%B", "--since=7 days ago"]
+    );
+}
+
+#[test]
+fn test_ticket() {
+    assert_eq!(ticket("feature/API-123\n"), Some("API-123".to_string()));
+    assert_eq!(
+        ticket("bugfix/WEB2-7-fix-login"),
+        Some("WEB2-7".to_string())
+    );
+    assert_eq!(ticket("main"), None);
+    assert_eq!(ticket("feature/api-123"), None);
+}
@@ -1,5 +1,17 @@
 use std::io::{Error, ErrorKind};
 
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    config::Pattern, diagnostic::Diagnostic, error::ValidationError, rules::scope_segments,
+};
+
+lazy_static! {
+    static ref SCOPE_BEFORE_TYPE: Regex = Regex::new(r"^\(([^()]*)\)(\w+):\s*(.*)$").unwrap();
+    static ref TYPE_IN_SCOPE: Regex = Regex::new(r"^([\w$.,/-]+)\((\w+)\):\s*(.*)$").unwrap();
+}
+
 #[derive(PartialEq, Debug)]
 pub(crate) enum State {
     Type,
@@ -8,21 +20,60 @@ pub(crate) enum State {
     Body,
 }
 
-pub fn parse(line: &str) -> Result<(String, String, String), Error> {
+/// Opt-in extensions of the header grammar.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ParseOptions {
+    /// Allows `@` in scopes, for package versions like `pkg@1.2.3`.
+    pub allow_at_in_scope: bool,
+}
+
+// Returns the type, scope, description, and whether the header is marked as
+// breaking with a `!` in front of the colon.
+pub fn parse(line: &str) -> Result<(String, String, String, bool), Error> {
+    parse_with(line, ParseOptions::default())
+}
+
+pub fn parse_with(
+    line: &str,
+    options: ParseOptions,
+) -> Result<(String, String, String, bool), Error> {
+    // Editors on Windows like to prefix files with a byte order mark.
+    let line = line.trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace());
+    if line.starts_with(['(', ':']) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'! The commit type is empty, there is nothing before the scope or the colon",
+        ));
+    }
+    if !line.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'! The header does not start with a commit type",
+        ));
+    }
     let mut _type = String::new();
     let mut _scope = String::new();
     let mut _description = String::new();
     let mut state = State::Type;
     let mut paren_count = 0;
     let mut valid_scope = false;
+    let mut breaking = false;
     for c in line.chars() {
         match state {
             State::Type => {
+                if breaking && c != ':' {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'! The breaking change marker '!' has to be followed by the colon",
+                    ));
+                }
                 if c.is_alphanumeric() || c == '_' {
                     _type.push(c);
                 } else if c == '(' {
                     state = State::Scope;
                     paren_count += 1;
+                } else if c == '!' && !_type.is_empty() {
+                    breaking = true;
                 } else if c == ':' {
                     state = State::Description;
                 } else {
@@ -33,6 +84,12 @@ pub fn parse(line: &str) -> Result<(String, String, String), Error> {
                 }
             }
             State::Scope => {
+                if breaking && c != ':' {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'!! The breaking change marker '!' has to be followed by the colon",
+                    ));
+                }
                 if c.is_alphanumeric()
                     || c == '_'
                     || c == ','
@@ -40,10 +97,19 @@ pub fn parse(line: &str) -> Result<(String, String, String), Error> {
                     || c == '.'
                     || c == '/'
                     || c == '-'
+                    || (c == '@' && options.allow_at_in_scope)
                 {
+                    if valid_scope {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'!! Found characters after the closing parenthesis of the scope",
+                        ));
+                    }
                     _scope.push(c);
                 } else if c == ')' {
                     valid_scope = true;
+                } else if c == '!' && valid_scope {
+                    breaking = true;
                 } else if c == ':' {
                     paren_count -= 1;
                     if paren_count == 0 {
@@ -73,6 +139,21 @@ pub fn parse(line: &str) -> Result<(String, String, String), Error> {
             _ => {}
         }
     }
+    if _type.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'! The commit type '{}' consists of digits only",
+                _type
+            ),
+        ));
+    }
+    if breaking && state != State::Body && state != State::Description {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Incorrect commit message, expected format 'TYPE([SCOPE]): MESSAGE\n[BODY]'!! Found the breaking change marker '!', but no colon after it",
+        ));
+    }
     if state != State::Body && state != State::Description {
         return Err(Error::new(
             ErrorKind::InvalidData,
@@ -86,96 +167,328 @@ pub fn parse(line: &str) -> Result<(String, String, String), Error> {
         _type.trim().to_string(),
         _scope.trim().to_string(),
         _description.trim().to_string(),
+        breaking,
     ))
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct CommitMessage {
-    pub(crate) commit_type: String,
-    pub(crate) required: Vec<String>,
+    pub commit_type: String,
+    pub required: Vec<String>,
+    pub forbidden: Vec<String>,
+    pub scopes: Vec<String>,
+    pub category: Option<String>,
+    pub help: Option<String>,
+    pub min_description_length: Option<usize>,
 }
 
-pub(crate) fn default_commit_types() -> Vec<CommitMessage> {
+pub fn default_commit_types() -> Vec<CommitMessage> {
     vec![
         CommitMessage {
             commit_type: "feat".to_string(),
             required: vec!["scope".to_string(), "description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "fix".to_string(),
             required: vec!["scope".to_string(), "description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "build".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "chore".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "ci".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "docs".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "perf".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "refactor".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "revert".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "style".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "test".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
     ]
 }
 
-pub fn validate(
-    spec: Vec<CommitMessage>,
+pub fn find<'a>(
+    spec: &'a [CommitMessage],
     ignore_case: bool,
     commit_type: &str,
-    scope: &str,
-    description: &str,
-) -> Result<bool, Error> {
-    let commit_type = spec.iter().find(|x| {
+) -> Option<&'a CommitMessage> {
+    spec.iter().find(|x| {
         x.commit_type == commit_type
-            || (ignore_case && x.commit_type.to_ascii_uppercase() == commit_type.to_ascii_uppercase())
+            || (ignore_case && x.commit_type.eq_ignore_ascii_case(commit_type))
+    })
+}
+
+// The Levenshtein distance, by chars.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Finds the type a misspelled type like `fet` stands for, i.e. the only
+/// configured type within an edit distance of 1.
+pub fn find_misspelled<'a>(
+    spec: &'a [CommitMessage],
+    ignore_case: bool,
+    commit_type: &str,
+) -> Option<&'a CommitMessage> {
+    let normalize = |name: &str| match ignore_case {
+        true => name.to_lowercase(),
+        false => name.to_string(),
+    };
+    let mut candidates = spec
+        .iter()
+        .filter(|x| edit_distance(&normalize(&x.commit_type), &normalize(commit_type)) <= 1);
+    match (candidates.next(), candidates.next()) {
+        (Some(found), None) => Some(found),
+        _ => None,
+    }
+}
+
+// Required fields are mandatory placeholders, optional parts are wrapped in
+// brackets like in the format of the error messages.
+pub fn template(commit_type: &CommitMessage) -> String {
+    let requires = |field: &str| commit_type.required.contains(&field.to_string());
+    let mut template = commit_type.commit_type.to_string();
+    if requires("scope") {
+        template.push_str("(<scope>)");
+    }
+    template.push_str(match requires("description") {
+        true => ": <description>",
+        false => ": [<description>]",
     });
-    match commit_type {
+    template.push_str("\n\n[<body>]\n\n[BREAKING CHANGE: <description>]\n");
+    template
+}
+
+// Detects the common mistake of swapping type and scope, either as
+// `(scope)type: description` or as `scope(type): description`.
+pub(crate) fn suggest_swap(
+    line: &str,
+    spec: &[CommitMessage],
+    ignore_case: bool,
+) -> Option<String> {
+    if let Some(captures) = SCOPE_BEFORE_TYPE.captures(line.trim()) {
+        return Some(format!(
+            "{}({}): {}",
+            &captures[2], &captures[1], &captures[3]
+        ));
+    }
+    let captures = TYPE_IN_SCOPE.captures(line.trim())?;
+    if find(spec, ignore_case, &captures[1]).is_none()
+        && find(spec, ignore_case, &captures[2]).is_some()
+    {
+        return Some(format!(
+            "{}({}): {}",
+            &captures[2], &captures[1], &captures[3]
+        ));
+    }
+    None
+}
+
+fn errors(
+    spec: &[CommitMessage],
+    ignore_case: bool,
+    commit_type: &str,
+    scope: &str,
+    description: &str,
+) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    match find(spec, ignore_case, commit_type) {
         Some(_type) => {
             if _type.required.contains(&"scope".to_string()) && scope.is_empty() {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    "Commit type requires a scope, but none given",
-                ));
+                errors.push(ValidationError::MissingScope);
+            }
+            if _type.forbidden.contains(&"scope".to_string()) && !scope.is_empty() {
+                errors.push(ValidationError::UnexpectedScope(scope.to_string()));
+            }
+            if !_type.scopes.is_empty() {
+                for segment in scope_segments(scope) {
+                    if !_type.scopes.iter().any(|allowed| allowed == segment) {
+                        errors.push(ValidationError::UnknownScope(
+                            segment.to_string(),
+                            _type.commit_type.to_string(),
+                            _type.scopes.clone(),
+                        ));
+                    }
+                }
             }
             if _type.required.contains(&"description".to_string()) && description.is_empty() {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    "Commit type requires a description, but none given",
-                ));
+                errors.push(ValidationError::MissingDescription);
+            }
+            match _type.min_description_length {
+                Some(min) if !description.is_empty() && description.chars().count() < min => {
+                    errors.push(ValidationError::DescriptionTooShort(min));
+                }
+                _ => {}
             }
-            return Ok(true);
         }
-        None => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Commit type not allowed",
+        None => errors.push(ValidationError::UnknownType(
+            commit_type.to_string(),
+            spec.iter()
+                .filter_map(|_type| {
+                    let help = _type.help.as_ref()?;
+                    Some((_type.commit_type.to_string(), help.to_string()))
+                })
+                .collect(),
         )),
     }
+    errors
+}
+
+pub(crate) fn check(
+    spec: &[CommitMessage],
+    ignore_case: bool,
+    commit_type: &str,
+    scope: &str,
+    description: &str,
+) -> Vec<Diagnostic> {
+    errors(spec, ignore_case, commit_type, scope, description)
+        .iter()
+        .map(|error| Diagnostic::error(error.rule(), &error.to_string()))
+        .collect()
+}
+
+// The body isn't part of the header, so it's checked on its own.
+pub(crate) fn check_body(
+    spec: &[CommitMessage],
+    ignore_case: bool,
+    commit_type: &str,
+    body: &str,
+) -> Option<Diagnostic> {
+    let _type = find(spec, ignore_case, commit_type)?;
+    if _type.required.contains(&"body".to_string()) && body.trim().is_empty() {
+        let error = ValidationError::MissingBody;
+        return Some(Diagnostic::error(error.rule(), &error.to_string()));
+    }
+    None
+}
+
+// Unlike the scopes of a type, this applies to every type.
+pub(crate) fn check_denied_scopes(denied: &[String], scope: &str) -> Vec<Diagnostic> {
+    scope_segments(scope)
+        .into_iter()
+        .filter(|segment| denied.iter().any(|name| name.eq_ignore_ascii_case(segment)))
+        .map(|segment| {
+            let error = ValidationError::ForbiddenScope(segment.to_string());
+            Diagnostic::error(error.rule(), &error.to_string())
+        })
+        .collect()
+}
+
+// Like the denied scopes this applies to every type, a commit without a
+// scope passes.
+pub(crate) fn check_allowed_scopes(allowed: &[String], scope: &str) -> Vec<Diagnostic> {
+    if allowed.is_empty() {
+        return vec![];
+    }
+    scope_segments(scope)
+        .into_iter()
+        .filter(|segment| !allowed.iter().any(|name| name == segment))
+        .map(|segment| {
+            let error = ValidationError::UnlistedScope(segment.to_string());
+            Diagnostic::error(error.rule(), &error.to_string())
+        })
+        .collect()
+}
+
+// Checks the raw header line, placeholders like `<scope>` make it impossible
+// to parse the header in the first place. Code in backticks, like
+// `<details>`, is quoted on purpose.
+pub(crate) fn check_placeholders(patterns: &[Pattern], line: &str) -> Vec<Diagnostic> {
+    let in_code = |start: usize| line[..start].matches('`').count() % 2 == 1;
+    patterns
+        .iter()
+        .filter_map(|pattern| {
+            pattern
+                .0
+                .find_iter(line)
+                .find(|placeholder| !in_code(placeholder.start()))
+        })
+        .map(|placeholder| {
+            let error = ValidationError::UnfilledPlaceholder(placeholder.as_str().to_string());
+            Diagnostic::error(error.rule(), &error.to_string())
+                .with_span(placeholder.start(), placeholder.end())
+        })
+        .collect()
+}
+
+// Covers both the `!` in the header and a `BREAKING CHANGE` footer.
+pub(crate) fn check_breaking(
+    allowed: &[String],
+    ignore_case: bool,
+    commit_type: &str,
+    breaking: bool,
+) -> Option<Diagnostic> {
+    let found = allowed
+        .iter()
+        .any(|name| name == commit_type || (ignore_case && name.eq_ignore_ascii_case(commit_type)));
+    if breaking && !found {
+        let error = ValidationError::BreakingNotAllowed(commit_type.to_string());
+        return Some(Diagnostic::error(error.rule(), &error.to_string()));
+    }
+    None
+}
+
+// The returned error wraps a `ValidationError`, which can be retrieved with
+// `get_ref` and `downcast_ref`.
+pub fn validate(
+    spec: Vec<CommitMessage>,
+    ignore_case: bool,
+    commit_type: &str,
+    scope: &str,
+    description: &str,
+) -> Result<bool, Error> {
+    match errors(&spec, ignore_case, commit_type, scope, description)
+        .into_iter()
+        .next()
+    {
+        Some(error) => Err(Error::new(ErrorKind::InvalidData, error)),
+        None => Ok(true),
+    }
 }
 
 #[test]
@@ -183,19 +496,34 @@ fn test_header_success() {
     let test_cases = vec![
         (
             "name:".to_string(),
-            ("name".to_string(), "".to_string(), "".to_string()),
+            ("name".to_string(), "".to_string(), "".to_string(), false),
         ),
         (
             "name(args): ".to_string(),
-            ("name".to_string(), "args".to_string(), "".to_string()),
+            (
+                "name".to_string(),
+                "args".to_string(),
+                "".to_string(),
+                false,
+            ),
         ),
         (
             "name: value".to_string(),
-            ("name".to_string(), "".to_string(), "value".to_string()),
+            (
+                "name".to_string(),
+                "".to_string(),
+                "value".to_string(),
+                false,
+            ),
         ),
         (
             "name(args): value".to_string(),
-            ("name".to_string(), "args".to_string(), "value".to_string()),
+            (
+                "name".to_string(),
+                "args".to_string(),
+                "value".to_string(),
+                false,
+            ),
         ),
         (
             "name(args): value: another_value".to_string(),
@@ -203,6 +531,7 @@ fn test_header_success() {
                 "name".to_string(),
                 "args".to_string(),
                 "value: another_value".to_string(),
+                false,
             ),
         ),
         (
@@ -211,6 +540,7 @@ fn test_header_success() {
                 "name".to_string(),
                 "arg1,arg2".to_string(),
                 "value".to_string(),
+                false,
             ),
         ),
         (
@@ -219,14 +549,32 @@ fn test_header_success() {
                 "name".to_string(),
                 "arg_1,arg-2,arg$3".to_string(),
                 "value".to_string(),
+                false,
+            ),
+        ),
+        (
+            "name!: value".to_string(),
+            (
+                "name".to_string(),
+                "".to_string(),
+                "value".to_string(),
+                true,
+            ),
+        ),
+        (
+            "name(args)!: value".to_string(),
+            (
+                "name".to_string(),
+                "args".to_string(),
+                "value".to_string(),
+                true,
             ),
         ),
     ];
     for (input, expected) in test_cases {
         match parse(&input) {
             Ok(header) => assert_eq!(header, expected, "Unexpected failure for value {}", input),
-            Err(e) => assert!(
-                false,
+            Err(e) => panic!(
                 "Should not have failed for '{}', but did with '{}'",
                 input, e
             ),
@@ -234,6 +582,79 @@ fn test_header_success() {
     }
 }
 
+#[test]
+fn test_breaking_marker() {
+    for (input, expected) in [("feat!: x", "feat"), ("fix!: y", "fix")] {
+        let (commit_type, scope, description, breaking) = parse(input).unwrap();
+        assert_eq!(commit_type, expected);
+        assert_eq!(scope, "");
+        assert!(!description.is_empty());
+        assert!(breaking, "Expected '{}' to be breaking", input);
+    }
+    assert!(!parse("feat: x").unwrap().3);
+    assert_eq!(
+        parse("fe!at: x").unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_header_start() {
+    for input in ["\u{feff}feat: x", "  feat: x", "\u{feff} feat: x"] {
+        assert_eq!(parse(input).unwrap().0, "feat", "Failed for '{}'", input);
+    }
+    for input in ["-feat: x", "*feat: x", "\u{feff}"] {
+        assert!(
+            parse(input)
+                .unwrap_err()
+                .to_string()
+                .ends_with("The header does not start with a commit type"),
+            "Unexpected error for '{}'",
+            input
+        );
+    }
+    for input in [": x", "(scope): x", "  (api)feat: x", "\u{feff}: x"] {
+        assert!(
+            parse(input).unwrap_err().to_string().ends_with(
+                "The commit type is empty, there is nothing before the scope or the colon"
+            ),
+            "Unexpected error for '{}'",
+            input
+        );
+    }
+    for input in ["123: x", "1(api): x"] {
+        assert!(
+            parse(input)
+                .unwrap_err()
+                .to_string()
+                .ends_with("consists of digits only"),
+            "Unexpected error for '{}'",
+            input
+        );
+    }
+    assert_eq!(parse("v2: x").unwrap().0, "v2");
+}
+
+#[test]
+fn test_breaking_marker_without_colon() {
+    for input in ["feat!", "feat(api)!"] {
+        let error = parse(input).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(
+            error
+                .to_string()
+                .ends_with("Found the breaking change marker '!', but no colon after it"),
+            "Unexpected error for '{}': {}",
+            input,
+            error
+        );
+    }
+    assert_eq!(
+        parse("feat!:").unwrap(),
+        ("feat".to_string(), "".to_string(), "".to_string(), true)
+    );
+}
+
 #[test]
 fn test_header_failure() {
     let test_cases = vec![
@@ -243,15 +664,42 @@ fn test_header_failure() {
         "name(args) value",
         "name(args: value",
         "name(arg.1/2*3): value",
+        "(args)name: value",
+        "name(args)more: value",
+        "na!me: value",
+        "name!!: value",
+        "name!(args): value",
+        "!: value",
+        "name(args)!x: value",
+        "name(args!): value",
     ];
     for input in test_cases {
-        match parse(&input) {
-            Ok(_) => assert!(false, "Should not have failed for '{}', but didn't", input),
+        match parse(input) {
+            Ok(_) => panic!("Should not have failed for '{}', but didn't", input),
             Err(e) => assert_eq!(ErrorKind::InvalidData, e.kind()),
         }
     }
 }
 
+#[test]
+fn test_suggest_swap() {
+    let test_cases = vec![
+        ("(api)feat: add endpoint", Some("feat(api): add endpoint")),
+        ("api(feat): add endpoint", Some("feat(api): add endpoint")),
+        ("feat(api): add endpoint", None),
+        ("docs(feat): document the feat type", None),
+        ("nope(wrong): x", None),
+    ];
+    for (input, expected) in test_cases {
+        assert_eq!(
+            suggest_swap(input, &default_commit_types(), false),
+            expected.map(|s| s.to_string()),
+            "Unexpected suggestion for '{}'",
+            input
+        );
+    }
+}
+
 #[test]
 fn test_validate_success() {
     let test_cases = vec![
@@ -277,7 +725,7 @@ fn test_validate_success() {
             description,
         );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 }
 
@@ -301,6 +749,35 @@ fn test_validate_failure() {
     }
 }
 
+#[test]
+fn test_check_rule_ids() {
+    let test_cases = vec![
+        ("not_allowed", "scope", "description", vec!["type-enum"]),
+        ("feat", "", "description", vec!["scope-required"]),
+        (
+            "feat",
+            "",
+            "",
+            vec!["scope-required", "description-required"],
+        ),
+        ("build", "scope", "", vec!["description-required"]),
+        ("build", "", "description", vec![]),
+    ];
+    for (commit_type, scope, description, expected) in test_cases {
+        let rules: Vec<&str> = check(
+            &default_commit_types(),
+            false,
+            commit_type,
+            scope,
+            description,
+        )
+        .iter()
+        .map(|d| d.code)
+        .collect();
+        assert_eq!(rules, expected, "Unexpected rules for '{}'", commit_type);
+    }
+}
+
 #[test]
 fn test_validate_ignore_cases_success() {
     let test_cases = vec![
@@ -318,7 +795,7 @@ fn test_validate_ignore_cases_success() {
             description,
         );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 }
 
@@ -328,10 +805,12 @@ fn test_validate_ignore_cases_more_cases() {
         CommitMessage {
             commit_type: "FEAT".to_string(),
             required: vec!["scope".to_string(), "description".to_string()],
+            ..Default::default()
         },
         CommitMessage {
             commit_type: "build".to_string(),
             required: vec!["description".to_string()],
+            ..Default::default()
         },
     ];
     let test_cases = vec![
@@ -341,16 +820,10 @@ fn test_validate_ignore_cases_more_cases() {
         ("BUILD", "", "description"),
     ];
     for (commit_type, scope, description) in test_cases {
-        let result = validate(
-            commit_types.clone(),
-            true,
-            commit_type,
-            scope,
-            description,
-        );
-        
+        let result = validate(commit_types.clone(), true, commit_type, scope, description);
+
         assert!(result.is_ok(), "Error: {}", result.unwrap_err());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 }
 
@@ -373,3 +846,210 @@ fn test_validate_ignore_cases_failure() {
         assert!(result.is_err());
     }
 }
+
+#[test]
+fn test_template() {
+    let commit_types = default_commit_types();
+    let feat = find(&commit_types, false, "feat").unwrap();
+    assert_eq!(
+        template(feat),
+        "feat(<scope>): <description>\n\n[<body>]\n\n[BREAKING CHANGE: <description>]\n"
+    );
+    let chore = find(&commit_types, false, "chore").unwrap();
+    assert!(template(chore).starts_with("chore: <description>\n"));
+    assert!(!template(chore).contains("<scope>"));
+    let wip = CommitMessage {
+        commit_type: "wip".to_string(),
+        ..Default::default()
+    };
+    assert!(template(&wip).starts_with("wip: [<description>]\n"));
+}
+
+#[test]
+fn test_parse_at_in_scope() {
+    let options = ParseOptions {
+        allow_at_in_scope: true,
+    };
+    for line in [
+        "feat(pkg@1.2.3): x",
+        "feat(pkg@1.2.3):x",
+        "feat(@scope/pkg@1.2.3)!: x",
+    ] {
+        let (commit_type, scope, description, _) = parse_with(line, options).unwrap();
+        assert_eq!(commit_type, "feat");
+        assert!(scope.contains("@"), "Unexpected scope for '{}'", line);
+        assert_eq!(description, "x");
+        assert!(parse(line).is_err(), "Should have failed for '{}'", line);
+    }
+    assert!(parse_with("feat(pkg@1.2.3) x", options).is_err());
+    assert!(parse_with("feat@1(pkg): x", options).is_err());
+}
+
+#[test]
+fn test_unknown_type_help() {
+    let mut commit_types = default_commit_types();
+    for commit_type in commit_types.iter_mut() {
+        commit_type.help = Some(format!("Help for {}", commit_type.commit_type));
+    }
+    let message = check(&commit_types, false, "wip", "", "message")[0]
+        .message
+        .to_string();
+    assert!(message.starts_with("Commit type not allowed, expected one of:\n"));
+    for commit_type in &commit_types {
+        let line = format!(
+            "\n  {}: Help for {}",
+            commit_type.commit_type, commit_type.commit_type
+        );
+        assert!(message.contains(&line), "Missing help in '{}'", message);
+    }
+
+    let diagnostics = check(&default_commit_types(), false, "wip", "", "message");
+    assert_eq!(diagnostics[0].message, "Commit type not allowed");
+}
+
+#[test]
+fn test_check_body() {
+    let commit_types = vec![CommitMessage {
+        commit_type: "feat".to_string(),
+        required: vec!["body".to_string()],
+        ..Default::default()
+    }];
+    assert!(check_body(&commit_types, false, "feat", "Details").is_none());
+    assert_eq!(
+        check_body(&commit_types, false, "feat", " \n\t\n")
+            .unwrap()
+            .code,
+        "body-required"
+    );
+    assert!(check_body(&default_commit_types(), false, "feat", "").is_none());
+}
+
+#[test]
+fn test_validate_min_description_length() {
+    let mut commit_types = default_commit_types();
+    commit_types[0].min_description_length = Some(10);
+    assert!(validate(commit_types.clone(), false, "feat", "api", "0123456789").unwrap());
+    let result = validate(commit_types.clone(), false, "feat", "api", "012345678").unwrap_err();
+    assert_eq!(
+        result.to_string(),
+        "Commit type requires a description of at least 10 characters"
+    );
+    assert!(validate(commit_types, false, "fix", "api", "short").unwrap());
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("feat", "feat"), 0);
+    assert_eq!(edit_distance("fet", "feat"), 1);
+    assert_eq!(edit_distance("feta", "feat"), 2);
+    assert_eq!(edit_distance("fxi", "fix"), 2);
+    assert_eq!(edit_distance("", "ci"), 2);
+    assert_eq!(edit_distance("dcos", "docs"), 2);
+}
+
+#[test]
+fn test_find_misspelled() {
+    let spec = default_commit_types();
+    let found = |commit_type| {
+        find_misspelled(&spec, false, commit_type).map(|found| found.commit_type.as_str())
+    };
+    assert_eq!(found("fet"), Some("feat"));
+    assert_eq!(found("fixx"), Some("fix"));
+    assert_eq!(found("dosc"), None);
+    assert_eq!(found("xyz"), None);
+    assert_eq!(found("Fet"), None);
+    assert_eq!(
+        find_misspelled(&spec, true, "Fet").map(|found| found.commit_type.as_str()),
+        Some("feat")
+    );
+    assert_eq!(found("cx"), Some("ci"));
+}
+
+#[test]
+fn test_check_placeholders() {
+    let patterns = crate::config::Config::default().placeholder_patterns;
+    assert!(check_placeholders(&patterns, "feat(api): add the endpoint").is_empty());
+    assert!(check_placeholders(&patterns, "feat(api): compare a <= b").is_empty());
+    for line in [
+        "fix(api): accept Vec<u8> payloads",
+        "fix(api): return Option<String>",
+        "docs: move `<details>` tags",
+    ] {
+        assert!(check_placeholders(&patterns, line).is_empty(), "{}", line);
+    }
+    assert_eq!(
+        check_placeholders(&patterns, "docs: move `<details>` to <target>")[0].message,
+        "Header still contains the placeholder '<target>' of a template"
+    );
+    let diagnostics = check_placeholders(&patterns, "feat(<scope>): <description>");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "header-placeholder");
+    assert_eq!(
+        diagnostics[0].message,
+        "Header still contains the placeholder '<scope>' of a template"
+    );
+    assert_eq!(
+        check_placeholders(&patterns, "fix: XXX handle errors")[0].message,
+        "Header still contains the placeholder 'XXX' of a template"
+    );
+    assert!(check_placeholders(&patterns, "fix: handle XXXL sizes").is_empty());
+    let todo = vec![Pattern::new("TODO:?").unwrap()];
+    assert_eq!(check_placeholders(&todo, "fix: TODO: describe").len(), 1);
+    assert!(check_placeholders(&[], "feat(<scope>): <description>").is_empty());
+}
+
+#[test]
+fn test_check_allowed_scopes() {
+    let allowed = vec!["api".to_string(), "ui".to_string()];
+    assert!(check_allowed_scopes(&allowed, "api").is_empty());
+    assert!(check_allowed_scopes(&allowed, "").is_empty());
+    assert!(check_allowed_scopes(&[], "anything").is_empty());
+    let diagnostics = check_allowed_scopes(&allowed, "api,cli/ui");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "scope-enum");
+    assert_eq!(
+        diagnostics[0].message,
+        "Scope 'cli' is not in the list of allowed scopes"
+    );
+}
+
+#[test]
+fn test_check_denied_scopes() {
+    let denied = vec!["misc".to_string(), "tmp".to_string()];
+    assert!(check_denied_scopes(&denied, "api").is_empty());
+    assert!(check_denied_scopes(&denied, "").is_empty());
+    let diagnostics = check_denied_scopes(&denied, "api,Misc/tmp");
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].code, "scope-denied");
+    assert_eq!(diagnostics[0].message, "Scope 'Misc' is not allowed");
+}
+
+#[test]
+fn test_check_breaking() {
+    let allowed = vec!["feat".to_string(), "fix".to_string()];
+    assert!(check_breaking(&allowed, false, "feat", true).is_none());
+    assert!(check_breaking(&allowed, false, "docs", false).is_none());
+    assert!(check_breaking(&allowed, true, "FEAT", true).is_none());
+    let diagnostic = check_breaking(&allowed, false, "docs", true).unwrap();
+    assert_eq!(diagnostic.code, "breaking-not-allowed");
+    assert_eq!(
+        diagnostic.message,
+        "Commit type 'docs' doesn't allow breaking changes"
+    );
+}
+
+#[test]
+fn test_validate_unexpected_scope() {
+    let commit_types = vec![CommitMessage {
+        commit_type: "chore".to_string(),
+        forbidden: vec!["scope".to_string()],
+        ..Default::default()
+    }];
+    assert!(validate(commit_types.clone(), false, "chore", "", "y").unwrap());
+    let error = validate(commit_types, false, "chore", "x", "y").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.get_ref().unwrap().downcast_ref::<ValidationError>(),
+        Some(&ValidationError::UnexpectedScope("x".to_string()))
+    );
+}
@@ -0,0 +1,62 @@
+use std::io::Error;
+
+use regex::escape;
+
+use crate::config::Pattern;
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+// An ignore file has a subject pattern per line. Patterns are globs, unless
+// they are wrapped in slashes, in which case they are a regular expression.
+// Blank lines and lines starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<Vec<Pattern>, Error> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            if line.len() > 1 && line.starts_with('/') && line.ends_with('/') {
+                Pattern::new(&line[1..line.len() - 1])
+            } else {
+                Pattern::new(&glob_to_regex(line))
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn is_ignored(patterns: &[Pattern], subject: &str) -> bool {
+    patterns.iter().any(|pattern| pattern.0.is_match(subject))
+}
+
+#[test]
+fn test_parse_ignore_file() {
+    let patterns = parse("# generated\n\nMerge branch *\n/^v\\d+\\.\\d+\\.\\d+$/\nWIP?").unwrap();
+    assert_eq!(patterns.len(), 3);
+
+    let test_cases = vec![
+        ("Merge branch 'main' into feature", true),
+        ("v1.2.3", true),
+        ("WIP!", true),
+        ("WIP", false),
+        ("feat: Merge branch handling", false),
+        ("v1.2.3-beta", false),
+    ];
+    for (subject, expected) in test_cases {
+        assert_eq!(
+            is_ignored(&patterns, subject),
+            expected,
+            "Unexpected result for '{}'",
+            subject
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind},
+    iter::Peekable,
+    str::Chars,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::String(text.to_string())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<Option<&str>> for Value {
+    fn from(value: Option<&str>) -> Self {
+        match value {
+            Some(text) => Value::from(text),
+            None => Value::Null,
+        }
+    }
+}
+
+impl Value {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries
+                .iter()
+                .find(|(existing, _)| existing == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn object(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(value) => write!(f, "{}", value),
+            Value::String(text) => write!(f, "{}", escape(text)),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", escape(key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("Invalid JSON, {}", message))
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: &str) -> Result<(), Error> {
+    for c in expected.chars() {
+        if chars.next() != Some(c) {
+            return Err(invalid(&format!("expected '{}'", expected)));
+        }
+    }
+    Ok(())
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, Error> {
+    expect(chars, "\"")?;
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some('b') => text.push('\u{8}'),
+                Some('f') => text.push('\u{c}'),
+                Some('u') => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    let c = u32::from_str_radix(&code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or(invalid(&format!("unsupported escape '\\u{}'", code)))?;
+                    text.push(c);
+                }
+                Some(c) => text.push(c),
+                None => return Err(invalid("unterminated string")),
+            },
+            Some(c) => text.push(c),
+            None => return Err(invalid("unterminated string")),
+        }
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, Error> {
+    skip_whitespace(chars);
+    let value = match chars.peek() {
+        Some('{') => {
+            chars.next();
+            let mut entries = vec![];
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_none() {
+                loop {
+                    skip_whitespace(chars);
+                    let key = parse_string(chars)?;
+                    skip_whitespace(chars);
+                    expect(chars, ":")?;
+                    entries.push((key, parse_value(chars)?));
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => break,
+                        _ => return Err(invalid("expected ',' or '}' in object")),
+                    }
+                }
+            }
+            Value::Object(entries)
+        }
+        Some('[') => {
+            chars.next();
+            let mut values = vec![];
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_none() {
+                loop {
+                    values.push(parse_value(chars)?);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => break,
+                        _ => return Err(invalid("expected ',' or ']' in array")),
+                    }
+                }
+            }
+            Value::Array(values)
+        }
+        Some('"') => Value::String(parse_string(chars)?),
+        Some('t') => expect(chars, "true").map(|_| Value::Bool(true))?,
+        Some('f') => expect(chars, "false").map(|_| Value::Bool(false))?,
+        Some('n') => expect(chars, "null").map(|_| Value::Null)?,
+        Some(_) => {
+            let mut number = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                number.push(c);
+            }
+            Value::Number(
+                number
+                    .parse()
+                    .map_err(|_| invalid(&format!("unexpected value '{}'", number)))?,
+            )
+        }
+        None => return Err(invalid("unexpected end of input")),
+    };
+    skip_whitespace(chars);
+    Ok(value)
+}
+
+pub(crate) fn parse(text: &str) -> Result<Value, Error> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    match chars.next() {
+        Some(c) => Err(invalid(&format!("unexpected '{}' after the value", c))),
+        None => Ok(value),
+    }
+}
+
+#[test]
+fn test_to_string() {
+    let value = object(vec![
+        ("type", Value::from("feat")),
+        ("scope", Value::from(None)),
+        ("valid", Value::from(true)),
+        ("count", Value::from(2)),
+        (
+            "lines",
+            Value::Array(vec![
+                Value::from("a \"quoted\"\nline"),
+                Value::from("\u{1}"),
+            ]),
+        ),
+    ]);
+    assert_eq!(
+        value.to_string(),
+        r#"{"type":"feat","scope":null,"valid":true,"count":2,"lines":["a \"quoted\"\nline","\u0001"]}"#
+    );
+}
+
+#[test]
+fn test_parse() {
+    let text = r#" {"rules": {"type-enum": [2, "always", ["feat", "fix"]], "max": -1.5e1},
+        "escaped": "a \"b\"\n\u00e9", "empty": {}, "list": [], "flags": [true, false, null]} "#;
+    let expected = object(vec![
+        (
+            "rules",
+            object(vec![
+                (
+                    "type-enum",
+                    Value::Array(vec![
+                        Value::from(2),
+                        Value::from("always"),
+                        Value::Array(vec![Value::from("feat"), Value::from("fix")]),
+                    ]),
+                ),
+                ("max", Value::Number(-15.0)),
+            ]),
+        ),
+        ("escaped", Value::from("a \"b\"\n\u{e9}")),
+        ("empty", Value::Object(vec![])),
+        ("list", Value::Array(vec![])),
+        (
+            "flags",
+            Value::Array(vec![Value::from(true), Value::from(false), Value::Null]),
+        ),
+    ]);
+    assert_eq!(parse(text).unwrap(), expected);
+    assert_eq!(parse(&expected.to_string()).unwrap(), expected);
+
+    for text in ["", "{", r#"{"a" 1}"#, "[1 2]", r#""open"#, "tru", "{} x"] {
+        assert_eq!(
+            parse(text).unwrap_err().kind(),
+            ErrorKind::InvalidData,
+            "Should have failed for '{}'",
+            text
+        );
+    }
+}
@@ -0,0 +1,559 @@
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read};
+
+pub use analysis::AnalysisResult;
+pub use commit::Commit;
+pub use config::Config;
+use config::EmptyScopeParens;
+use diagnostic::Diagnostic;
+
+pub mod analysis;
+pub mod commit;
+pub mod commitlint;
+pub mod config;
+pub mod diagnostic;
+pub mod error;
+pub mod fix;
+mod footer;
+pub mod header;
+pub mod ignore;
+mod json;
+pub mod output;
+mod rules;
+pub mod stats;
+mod toml;
+
+fn parse_stream<R: Read>(
+    message_stream: BufReader<R>,
+    options: header::ParseOptions,
+) -> Result<Commit, Error> {
+    let mut lines = message_stream.lines();
+    let first_line = lines
+        .next()
+        .ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "Failed to read first line",
+        ))?
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let parsed_header = header::parse_with(&first_line, options)?;
+    let parsed = lines.fold("".to_string(), |mut acc, lines| {
+        acc.push_str(lines.unwrap_or("".to_string()).as_str());
+        acc.push('\n');
+        acc
+    });
+
+    Ok(Commit::new(parsed_header, &parsed))
+}
+
+pub fn parse<R: Read>(message: R) -> Result<Commit, Error> {
+    parse_with(message, header::ParseOptions::default())
+}
+
+/// Like `parse`, with extensions of the header grammar enabled.
+pub fn parse_with<R: Read>(message: R, options: header::ParseOptions) -> Result<Commit, Error> {
+    let message_stream = BufReader::new(message);
+    parse_stream(message_stream, options)
+}
+
+/// Reads a commit from a JSON object with the fields `type`, `scope`,
+/// `description` and `body`, of which only `type` is required. The footers
+/// are parsed from the body.
+pub fn parse_fields(text: &str) -> Result<Commit, Error> {
+    let value = json::parse(text)?;
+    let field = |name: &str| match value.get(name) {
+        Some(json::Value::String(text)) => Ok(Some(text.to_string())),
+        None | Some(json::Value::Null) => Ok(None),
+        Some(other) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Expected the field '{}' to be a string, got {}",
+                name, other
+            ),
+        )),
+    };
+    let commit_type = field("type")?.ok_or(Error::new(
+        ErrorKind::InvalidData,
+        "Expected the field 'type' in the input",
+    ))?;
+    let breaking = match value.get("breaking") {
+        Some(json::Value::Bool(breaking)) => *breaking,
+        _ => false,
+    };
+    Ok(Commit::new(
+        (
+            commit_type,
+            field("scope")?.unwrap_or_default(),
+            field("description")?.unwrap_or_default(),
+            breaking,
+        ),
+        &field("body")?.unwrap_or_default(),
+    ))
+}
+
+// Squash merges put the PR title in the first line and the squashed commits
+// below it, so look for the first line that parses as a conventional header.
+fn parse_squashed(message: &str, options: header::ParseOptions) -> Option<(usize, Commit)> {
+    let lines: Vec<&str> = message.lines().collect();
+    lines.iter().enumerate().skip(1).find_map(|(index, line)| {
+        match header::parse_with(line, options) {
+            Ok(parsed_header) => Some((
+                index,
+                Commit::new(parsed_header, &lines[index + 1..].join("\n")),
+            )),
+            Err(_) => None,
+        }
+    })
+}
+
+/// Parses and validates a commit message in one go.
+///
+/// ```
+/// use rcop::{analyze, Config};
+///
+/// let config = Config::default();
+/// let result = analyze("feat(api): add an endpoint", &config);
+/// assert!(result.valid());
+/// assert_eq!(result.commit.scope, "api");
+///
+/// let result = analyze("feat: add an endpoint", &config);
+/// assert!(!result.valid());
+/// assert_eq!(result.diagnostics[0].code, "scope-required");
+/// ```
+pub fn analyze(message: &str, config: &Config) -> AnalysisResult {
+    let first_line = message.lines().next().unwrap_or("");
+    if ignore::is_ignored(&config.ignore_patterns, first_line.trim()) {
+        return AnalysisResult {
+            commit: parse_with(message.as_bytes(), config.parse_options).unwrap_or_else(|_| {
+                Commit {
+                    description: first_line.to_string(),
+                    ..Commit::default()
+                }
+            }),
+            skipped: Some("ignored".to_string()),
+            ..AnalysisResult::default()
+        };
+    }
+    let mut notes = vec![];
+    let mut header_line = first_line;
+    let mut header_index = 0;
+    let parsed = parse_with(message.as_bytes(), config.parse_options).or_else(|err| {
+        match (
+            config.squash_aware,
+            parse_squashed(message, config.parse_options),
+        ) {
+            (true, Some((index, commit))) => {
+                header_line = message.lines().nth(index).unwrap_or("");
+                header_index = index;
+                notes.push(Diagnostic::warning(
+                    "squash-header",
+                    &format!(
+                        "The first line is not a conventional header, validated line {} instead",
+                        index + 1
+                    ),
+                ));
+                Ok(commit)
+            }
+            _ => Err(err),
+        }
+    });
+    let suggestion = header::suggest_swap(first_line, &config.commit_types, config.ignore_case)
+        .map(|suggestion| format!(", did you mean '{}'?", suggestion))
+        .unwrap_or_default();
+    let header_offset: usize = message
+        .split_inclusive('\n')
+        .take(header_index)
+        .map(str::len)
+        .sum();
+    let placeholders: Vec<Diagnostic> =
+        header::check_placeholders(&config.placeholder_patterns, header_line)
+            .into_iter()
+            .map(|diagnostic| diagnostic.offset(header_offset))
+            .collect();
+    let mut checked = vec!["header-format"];
+    if !config.placeholder_patterns.is_empty() {
+        checked.push("header-placeholder");
+    }
+    checked.retain(|rule| !config.is_disabled(rule));
+    let commit = match parsed {
+        Ok(commit) => commit,
+        // An unfilled template can't be parsed, but the placeholder is what
+        // has to be fixed.
+        Err(_) if !placeholders.is_empty() => {
+            return AnalysisResult {
+                diagnostics: placeholders,
+                checked,
+                ..AnalysisResult::default()
+            }
+        }
+        Err(err) => {
+            return AnalysisResult {
+                diagnostics: vec![Diagnostic::error(
+                    "header-format",
+                    &format!("{}{}", err, suggestion),
+                )
+                .with_span(0, first_line.len())],
+                checked,
+                ..AnalysisResult::default()
+            }
+        }
+    };
+    let mut result = analyze_commit(commit, config);
+    if result.skipped.is_some() {
+        return result;
+    }
+    for diagnostic in result.diagnostics.iter_mut() {
+        if diagnostic.code == "type-enum" {
+            diagnostic.message.push_str(&suggestion);
+        }
+        if diagnostic.span.is_none() {
+            diagnostic.span = field_span(diagnostic.code, header_line, &result.commit)
+                .map(|(start, end)| (start + header_offset, end + header_offset));
+        }
+    }
+    result.diagnostics.extend(rules::empty_body(message));
+    if let Some(max) = config.max_message_bytes {
+        result
+            .diagnostics
+            .extend(rules::max_message_bytes(max, message));
+    }
+    if let Some(max) = config.max_consecutive_blank_lines {
+        result
+            .diagnostics
+            .extend(rules::consecutive_blank_lines(max, message));
+    }
+    result.diagnostics.extend(
+        rules::empty_scope_parens(config.empty_scope_parens, header_line)
+            .map(|diagnostic| diagnostic.offset(header_offset)),
+    );
+    if config.strict_separator_spacing {
+        result.diagnostics.extend(
+            rules::separator_spacing(header_line)
+                .map(|diagnostic| diagnostic.offset(header_offset)),
+        );
+    }
+    result.diagnostics.extend(placeholders);
+    result.diagnostics.extend(notes);
+    config.apply_severities(&mut result.diagnostics);
+    checked.extend([
+        "body-empty",
+        "max-message-bytes",
+        "body-blank-lines",
+        "scope-empty",
+    ]);
+    checked.retain(|rule| match *rule {
+        "max-message-bytes" => config.max_message_bytes.is_some(),
+        "body-blank-lines" => config.max_consecutive_blank_lines.is_some(),
+        "scope-empty" => config.empty_scope_parens != EmptyScopeParens::Accept,
+        _ => true,
+    });
+    if config.strict_separator_spacing {
+        checked.push("separator-spacing");
+    }
+    if config.squash_aware {
+        checked.push("squash-header");
+    }
+    checked.retain(|rule| !config.is_disabled(rule));
+    checked.append(&mut result.checked);
+    result.checked = checked;
+    result
+}
+
+// The checks of the parsed commit don't know where its fields are, so they
+// are looked up in the header line for the rules about a single field.
+fn field_span(code: &str, header: &str, commit: &Commit) -> Option<(usize, usize)> {
+    let start = header.len() - header.trim_start().len();
+    let rest = &header[start..];
+    let description_end = header.trim_end().len();
+    let description_start = description_end.checked_sub(commit.description.len())?;
+    let has_description = header[..description_end].ends_with(&commit.description);
+    match code {
+        "type-enum" if rest.starts_with(&commit.commit_type) => {
+            Some((start, start + commit.commit_type.len()))
+        }
+        "scope-case" => {
+            let scope_start = start + commit.commit_type.len() + 1;
+            rest.strip_prefix(&commit.commit_type)?
+                .strip_prefix('(')?
+                .starts_with(&commit.scope)
+                .then_some((scope_start, scope_start + commit.scope.len()))
+        }
+        "subject-case" if has_description => Some((description_start, description_end)),
+        "trailing-period" if has_description => Some((description_end - 1, description_end)),
+        _ => None,
+    }
+}
+
+// The checks of the configured types and scopes, which `--prefix-pattern`
+// replaces.
+fn check_type_and_scope(commit: &Commit, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = header::check(
+        &config.commit_types,
+        config.ignore_case,
+        &commit.commit_type,
+        &commit.scope,
+        &commit.description,
+    );
+    diagnostics.extend(header::check_body(
+        &config.commit_types,
+        config.ignore_case,
+        &commit.commit_type,
+        &commit.body,
+    ));
+    diagnostics.extend(header::check_denied_scopes(
+        &config.deny_scopes,
+        &commit.scope,
+    ));
+    diagnostics.extend(header::check_allowed_scopes(
+        &config.allowed_scopes,
+        &commit.scope,
+    ));
+    if let Some(allowed) = &config.breaking_allowed_for {
+        diagnostics.extend(header::check_breaking(
+            allowed,
+            config.ignore_case,
+            &commit.commit_type,
+            commit.breaking,
+        ));
+    }
+    diagnostics
+}
+
+// The ids of the rules `check_type_and_scope` runs for the config.
+fn checked_type_and_scope(config: &Config) -> Vec<&'static str> {
+    let mut checked = vec![
+        "type-enum",
+        "scope-required",
+        "scope-forbidden",
+        "scope-enum",
+        "description-required",
+        "description-min-length",
+        "body-required",
+    ];
+    if !config.deny_scopes.is_empty() {
+        checked.push("scope-denied");
+    }
+    if config.breaking_allowed_for.is_some() {
+        checked.push("breaking-not-allowed");
+    }
+    checked
+}
+
+/// Validates a commit that is already split into its fields, like one
+/// returned by `parse` or `parse_fields`.
+pub fn analyze_commit(mut commit: Commit, config: &Config) -> AnalysisResult {
+    let mut notes = vec![];
+    if config.fuzzy_type
+        && header::find(
+            &config.commit_types,
+            config.ignore_case,
+            &commit.commit_type,
+        )
+        .is_none()
+    {
+        if let Some(found) = header::find_misspelled(
+            &config.commit_types,
+            config.ignore_case,
+            &commit.commit_type,
+        ) {
+            notes.push(Diagnostic::warning(
+                "type-fuzzy",
+                &format!(
+                    "Commit type '{}' is not known, accepted as '{}'",
+                    commit.commit_type, found.commit_type
+                ),
+            ));
+            commit.commit_type = found.commit_type.clone();
+        }
+    }
+    if !config.only_types.is_empty() && !config.only_types.contains(&commit.commit_type) {
+        return AnalysisResult {
+            commit,
+            skipped: Some("skipped".to_string()),
+            ..AnalysisResult::default()
+        };
+    }
+
+    let (mut diagnostics, mut checked) = match &config.prefix_pattern {
+        Some(pattern) => (
+            rules::prefix_pattern(pattern, &commit.commit_type, &commit.scope)
+                .into_iter()
+                .collect(),
+            vec!["prefix-pattern"],
+        ),
+        None => (
+            check_type_and_scope(&commit, config),
+            checked_type_and_scope(config),
+        ),
+    };
+    diagnostics.extend(rules::check(config, &commit));
+    diagnostics.extend(notes);
+    config.apply_severities(&mut diagnostics);
+    checked.extend(rules::checked(config));
+    if config.fuzzy_type {
+        checked.push("type-fuzzy");
+    }
+    checked.retain(|rule| !config.is_disabled(rule));
+    let category = header::find(
+        &config.commit_types,
+        config.ignore_case,
+        &commit.commit_type,
+    )
+    .and_then(|commit_type| commit_type.category.clone());
+    AnalysisResult {
+        commit,
+        diagnostics,
+        skipped: None,
+        category,
+        checked,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_valid_input() {
+        let input = b"feat(module): Add a new feature.\nThis is the first line of the feature.\nAnd this is the last line.";
+        let expected_output = Commit {
+            commit_type: "feat".to_string(),
+            scope: "module".to_string(),
+            description: "Add a new feature.".to_string(),
+            body: "This is the first line of the feature.\nAnd this is the last line.".to_string(),
+            footers: vec![],
+            breaking: false,
+        };
+        let result = parse(Cursor::new(input)).unwrap();
+        assert_eq!(result, expected_output);
+    }
+
+    #[test]
+    fn test_parse_footers() {
+        let input = b"fix(api): handle timeouts\n\nRetry twice before giving up.\n\nCloses #12\nCo-authored-by: Jane Doe <jane@example.com>";
+        let result = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            result.footers,
+            vec![
+                ("Closes".to_string(), "#12".to_string()),
+                (
+                    "Co-authored-by".to_string(),
+                    "Jane Doe <jane@example.com>".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_breaking() {
+        assert!(
+            parse(Cursor::new(b"feat!: drop the v1 api"))
+                .unwrap()
+                .breaking
+        );
+        assert!(
+            parse(Cursor::new(b"feat(api)!: drop the v1 api"))
+                .unwrap()
+                .breaking
+        );
+        assert!(
+            parse(Cursor::new(
+                b"feat(api): drop the v1 api\n\nBREAKING CHANGE: v1 is gone"
+            ))
+            .unwrap()
+            .breaking
+        );
+        assert!(
+            !parse(Cursor::new(b"feat(api): add the v2 api"))
+                .unwrap()
+                .breaking
+        );
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let commit = parse_fields(
+            r#"{"type": "feat", "scope": "api", "description": "add endpoint", "body": "Details\n\nRefs: #1"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            commit,
+            Commit {
+                commit_type: "feat".to_string(),
+                scope: "api".to_string(),
+                description: "add endpoint".to_string(),
+                body: "Details\n\nRefs: #1".to_string(),
+                footers: vec![("Refs".to_string(), "#1".to_string())],
+                breaking: false,
+            }
+        );
+        assert_eq!(
+            parse_fields(r#"{"type": "docs"}"#).unwrap().commit_type,
+            "docs"
+        );
+        for text in [r#"{"scope": "api"}"#, r#"{"type": 1}"#, "feat: x"] {
+            assert_eq!(
+                parse_fields(text).unwrap_err().kind(),
+                ErrorKind::InvalidData,
+                "Should have failed for '{}'",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_blank_body() {
+        let commit = parse(Cursor::new(b"feat(api): add endpoint\n\n  \n\t\n")).unwrap();
+        assert_eq!(commit.body, "");
+    }
+
+    #[test]
+    fn test_analyze_blank_body() {
+        let config = Config {
+            commit_types: vec![header::CommitMessage {
+                commit_type: "feat".to_string(),
+                required: vec!["description".to_string(), "body".to_string()],
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+        let result = analyze("feat: add endpoint\n\n  \n\n", &config);
+        let rules: Vec<&str> = result.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["body-required", "body-empty"]);
+        assert!(analyze("feat: add endpoint\n\nDetails", &config).valid());
+        assert!(analyze("docs: typo\n", &Config::default())
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_input_with_fixup() {
+        let input = b"fixup! fix: This is a fixup commit.\nThis is another line of the commit.\nAnd this is the last line.";
+        let expected_output = ErrorKind::InvalidData;
+        let result = parse(Cursor::new(input)).unwrap_err().kind();
+        assert_eq!(result, expected_output);
+    }
+
+    #[test]
+    fn test_parse_invalid_input() {
+        let input = b"This is not a valid input because it does not start with a keyword.";
+        let expected_output = ErrorKind::InvalidData;
+        let result = parse(Cursor::new(input)).unwrap_err().kind();
+        assert_eq!(result, expected_output);
+    }
+
+    #[test]
+    fn test_parse_input_without_colon() {
+        let input = b"fixup! fix This is a fixup commit.\nThis is another line of the commit.\nAnd this is the last line.";
+        let expected_output = ErrorKind::InvalidData;
+        let result = parse(Cursor::new(input)).unwrap_err().kind();
+        assert_eq!(result, expected_output);
+    }
+
+    #[test]
+    fn test_parse_input_without_parenthesis() {
+        let input = b"feature module: Add a new feature.\nThis is the first line of the feature.\nAnd this is the last line.";
+        let expected_output = ErrorKind::InvalidData;
+        let result = parse(Cursor::new(input)).unwrap_err().kind();
+        assert_eq!(result, expected_output);
+    }
+}
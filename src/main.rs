@@ -1,47 +1,32 @@
 use std::{
     env,
-    io::{stdin, BufRead, BufReader, Error, ErrorKind, Read},
-    process,
+    fs::{self, File},
+    io::{stdin, stdout, BufRead, Cursor, Error, ErrorKind, Read, Write},
+    path::Path,
+    process, slice, thread,
 };
 
-use header::{validate, CommitMessage};
-use prettytable::{format, row, Table};
-
-use crate::header::default_commit_types;
-
-mod header;
-
-fn parse_stream<R: Read>(
-    message_stream: BufReader<R>,
-) -> Result<(String, String, String, String), Error> {
-    let mut lines = message_stream.lines();
-    let first_line = lines
-        .next()
-        .ok_or(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to read first line",
-        ))?
-        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
-    let parsed_header = header::parse(&first_line)?;
-    let parsed = lines.fold("".to_string(), |mut acc, lines| {
-        acc.push_str(lines.unwrap_or("".to_string()).as_str());
-        acc.push_str("\n");
-        acc
-    });
-
-    Ok((
-        parsed_header.0,
-        parsed_header.1,
-        parsed_header.2,
-        parsed.trim().to_string(),
-    ))
-}
+use rcop::{
+    analyze, analyze_commit, commitlint,
+    config::{
+        parse_codeowners, parse_empty_scope_parens, parse_issue_footer_style, parse_list_file,
+        parse_release_commit, parse_scope_case, parse_severity_overrides, parse_subject_case,
+        EmptyScopeParens, Pattern,
+    },
+    diagnostic::Diagnostic,
+    fix::{normalize, suggest_header},
+    header::{self, CommitMessage},
+    ignore,
+    output::{
+        parse_format, render_changelog, render_compact, render_diff, render_github, render_json,
+        render_json_error, render_markdown, render_stats, render_table, render_toml, render_types,
+        Format,
+    },
+    stats, AnalysisResult, Config,
+};
 
-pub fn parse<R: Read>(message: R) -> Result<(String, String, String, String), Error> {
-    let message_stream = BufReader::new(message);
-    let parsed = parse_stream(message_stream);
-    parsed
-}
+mod git;
+mod profile;
 
 fn parse_commit_types(text: String) -> Vec<CommitMessage> {
     let mut commit_messages: Vec<CommitMessage> = vec![];
@@ -49,184 +34,3869 @@ fn parse_commit_types(text: String) -> Vec<CommitMessage> {
         return commit_messages;
     }
     for item in text.split(";").map(|s| s.to_string()) {
-        let parts: Vec<String> = item.split("=").map(|s| s.to_string()).collect();
-        let commit_type = parts[0].to_string();
-        let required: Vec<String> = {
-            let part = parts.get(1).unwrap_or(&"".to_string()).to_string();
+        let (commit_type, part) = item.split_once('=').unwrap_or((&item, ""));
+        let commit_type = commit_type.to_string();
+        let mut required: Vec<String> = {
             part.split(",")
                 .map(|s| s.to_string())
                 .filter(|s| !s.is_empty())
                 .collect()
         };
+        // `scope:ui|web` requires a scope out of the list, `scope:$frontend`
+        // one out of a scope set that is expanded later on.
+        // `description>=10` requires a description of at least 10 characters.
+        let mut scopes = vec![];
+        let mut min_description_length = None;
+        for field in required.iter_mut() {
+            // An empty list is left as it is, to be rejected on validation.
+            let allowed: Vec<String> = field
+                .strip_prefix("scope:")
+                .map(|allowed| {
+                    allowed
+                        .split('|')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            if !allowed.is_empty() {
+                scopes = allowed;
+                *field = "scope".to_string();
+            }
+            if let Some(Ok(min)) = field
+                .strip_prefix("description>=")
+                .map(|min| min.trim().parse())
+            {
+                min_description_length = Some(min);
+                *field = "description".to_string();
+            }
+        }
         commit_messages.push(CommitMessage {
             commit_type,
             required,
+            scopes,
+            min_description_length,
+            ..Default::default()
         });
     }
     commit_messages
 }
 
-fn parse_args() -> Result<(bool, bool, Vec<CommitMessage>), Error> {
-    let mut dont_exit_on_errors = false;
-    let mut ignore_case = false;
-    let mut commit_types = default_commit_types();
+fn validate_commit_types(commit_types: &[CommitMessage]) -> Result<(), Error> {
+    for (index, commit_type) in commit_types.iter().enumerate() {
+        if commit_type.commit_type.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Invalid types config, the commit type at position {} has no name",
+                    index + 1
+                ),
+            ));
+        }
+        if commit_types[..index]
+            .iter()
+            .any(|other| other.commit_type == commit_type.commit_type)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Invalid types config, the commit type '{}' is defined more than once",
+                    commit_type.commit_type
+                ),
+            ));
+        }
+        if let Some(field) = commit_type
+            .required
+            .iter()
+            .find(|field| field.starts_with("description>="))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Invalid types config, expected a number in '{}' of the commit type '{}'",
+                    field, commit_type.commit_type
+                ),
+            ));
+        }
+        if commit_type
+            .required
+            .iter()
+            .any(|field| field.starts_with("scope:"))
+        {
+            return Err(empty_scopes(&commit_type.commit_type));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum InputFormat {
+    #[default]
+    Message,
+    Fields,
+}
+
+fn parse_input_format(text: &str) -> Result<InputFormat, Error> {
+    match text {
+        "message" => Ok(InputFormat::Message),
+        "fields" => Ok(InputFormat::Fields),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown input format '{}', expected one of: message, fields",
+                text
+            ),
+        )),
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    dont_exit_on_errors: bool,
+    batch: bool,
+    file: Option<String>,
+    since: Option<String>,
+    branch_ticket: bool,
+    staged: bool,
+    types_stdin: bool,
+    stats: bool,
+    compact: bool,
+    changelog: bool,
+    parallel: bool,
+    changelog_titles: Vec<(String, String)>,
+    type_order: Vec<String>,
+    fail_fast: bool,
+    title: Option<String>,
+    output_file: Option<String>,
+    preprocess: Option<String>,
+    ignore_file: Option<String>,
+    report_only: bool,
+    exit_zero: bool,
+    null_output: bool,
+    skip_identical: bool,
+    commit_source: Option<String>,
+    baseline: Vec<String>,
+    write_baseline: Option<String>,
+    diff_suggest: bool,
+    normalize_output: bool,
+    list_types: bool,
+    print_schema: bool,
+    daemon: bool,
+    template: Option<String>,
+    input_format: InputFormat,
+    format: Format,
+    config: Config,
+}
+
+// The rows of the matrix replace the scopes of a type given with `--types`,
+// `*` allows any scope.
+fn apply_type_scopes(
+    commit_types: &mut [CommitMessage],
+    type_scopes: &[(String, Vec<String>)],
+) -> Result<(), Error> {
+    for (name, scopes) in type_scopes {
+        let commit_type = commit_types
+            .iter_mut()
+            .find(|commit_type| commit_type.commit_type == *name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Invalid type-scopes config, the commit type '{}' is not defined",
+                        name
+                    ),
+                )
+            })?;
+        commit_type.scopes = match scopes.as_slice() {
+            [any] if any == "*" => vec![],
+            scopes => scopes.to_vec(),
+        };
+    }
+    Ok(())
+}
+
+fn empty_scopes(commit_type: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Invalid types config, the commit type '{}' allows no scope, expected at least one after 'scope:'",
+            commit_type
+        ),
+    )
+}
+
+fn expand_scope_sets(
+    commit_types: &mut [CommitMessage],
+    scope_sets: &[(String, Vec<String>)],
+) -> Result<(), Error> {
+    for commit_type in commit_types.iter_mut() {
+        let mut scopes = vec![];
+        for scope in &commit_type.scopes {
+            match scope.strip_prefix('$') {
+                Some(name) => {
+                    let (_, set) = scope_sets
+                        .iter()
+                        .rev()
+                        .find(|(existing, _)| existing == name)
+                        .ok_or_else(|| {
+                            Error::new(
+                                ErrorKind::InvalidData,
+                                format!(
+                                    "Invalid types config, the commit type '{}' references the undefined scope set '{}'",
+                                    commit_type.commit_type, scope
+                                ),
+                            )
+                        })?;
+                    scopes.extend(set.iter().cloned());
+                }
+                None => scopes.push(scope.to_string()),
+            }
+        }
+        if scopes.is_empty() && !commit_type.scopes.is_empty() {
+            return Err(empty_scopes(&commit_type.commit_type));
+        }
+        commit_type.scopes = scopes;
+    }
+    Ok(())
+}
+
+fn value_of<'a>(args: &'a [String], index: usize, name: &str) -> Result<&'a String, Error> {
+    args.get(index + 1).ok_or(Error::new(
+        ErrorKind::InvalidData,
+        format!("Missing argument for {}", name),
+    ))
+}
+
+fn parse_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn parse_number(text: &str) -> Result<usize, Error> {
+    text.parse().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Expected a number, but got '{}'", text),
+        )
+    })
+}
+
+fn parse_mapping(text: &str) -> Vec<(String, Vec<String>)> {
+    text.split(';')
+        .filter_map(|item| item.split_once('='))
+        .map(|(category, types)| (category.trim().to_string(), parse_list(types)))
+        .collect()
+}
+
+// Unlike other mappings, the values are free text that may contain commas,
+// e.g. `feat=A new feature, visible to users;fix=A bug fix`.
+fn parse_help(text: &str) -> Vec<(String, String)> {
+    text.split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, help)| (name.trim().to_string(), help.trim().to_string()))
+        .collect()
+}
+
+// Inserts the options of the config files right after the program name, so
+// the options given on the command line take precedence.
+fn with_config_file(args: Vec<String>) -> Result<Vec<String>, Error> {
+    let profile = match args.iter().position(|arg| arg == "--profile") {
+        Some(index) => Some(value_of(&args, index, "profile")?.as_str()),
+        None => None,
+    };
+    let mut files = vec![];
+    for (index, _) in args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| *arg == "--config")
+    {
+        files.push(profile::parse(&fs::read_to_string(value_of(
+            &args, index, "config",
+        )?)?)?);
+    }
+    match (files.is_empty(), profile) {
+        (true, Some(_)) => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "--profile requires a config file passed with --config",
+            ))
+        }
+        (true, None) => return Ok(args),
+        _ => {}
+    }
+    let mut expanded = args[..1].to_vec();
+    expanded.extend(profile::layer(&files, profile)?);
+    expanded.extend(args[1..].iter().cloned());
+    Ok(expanded)
+}
+
+fn parse_args(args: Vec<String>) -> Result<Args, Error> {
+    let args = with_config_file(args)?;
+    let mut parsed = Args::default();
+    // The commitlint config is the base the other arguments are applied to.
+    if let Some(index) = args.iter().position(|arg| arg == "--commitlint-config") {
+        let path = value_of(&args, index, "commitlint-config")?;
+        parsed.config = commitlint::parse(&fs::read_to_string(path)?)?;
+    }
+    let mut categories = vec![];
+    let mut type_help = vec![];
+    let mut no_scope_for = vec![];
+    let mut require_description_for = vec![];
+    let mut require_type_in: Option<Vec<String>> = None;
+    let mut scope_sets = vec![];
+    let mut type_scopes = vec![];
 
-    for (index, argument) in env::args().enumerate() {
+    for (index, argument) in args.iter().enumerate() {
         match argument.as_str() {
             "--dont-exit-on-errors" | "-e" => {
-                dont_exit_on_errors = true;
+                parsed.dont_exit_on_errors = true;
             }
             "--ignore-case" | "-i" => {
-                ignore_case = true;
-            }
-            "--types" | "-t" => match env::args().nth(index + 1) {
-                Some(arg) => commit_types = parse_commit_types(arg),
-                None => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        "Missing argument for types",
-                    ))
+                parsed.config.ignore_case = true;
+            }
+            "--types" | "-t" => {
+                parsed.config.commit_types =
+                    parse_commit_types(value_of(&args, index, "types")?.to_string())
+            }
+            "--file" => parsed.file = Some(value_of(&args, index, "file")?.to_string()),
+            "--since" => {
+                parsed.since = Some(git::parse_duration(value_of(&args, index, "since")?)?);
+                parsed.batch = true;
+            }
+            "--staged" => {
+                parsed.staged = true;
+            }
+            "--branch-ticket" => {
+                parsed.branch_ticket = true;
+            }
+            "--types-stdin" => {
+                parsed.types_stdin = true;
+            }
+            "--batch" | "-b" => {
+                parsed.batch = true;
+            }
+            "--breaking-allowed-for" => {
+                parsed.config.breaking_allowed_for =
+                    Some(parse_list(value_of(&args, index, "breaking-allowed-for")?))
+            }
+            "--require-type-in" => {
+                require_type_in = Some(parse_list(value_of(&args, index, "require-type-in")?))
+            }
+            "--only-types" => {
+                parsed.config.only_types = parse_list(value_of(&args, index, "only-types")?)
+            }
+            "--strict-separator-spacing" => {
+                parsed.config.strict_separator_spacing = true;
+            }
+            "--warn-trailing-issue-ref" => {
+                parsed.config.warn_trailing_issue_ref = true;
+            }
+            "--warn-trailing-ellipsis" => {
+                parsed.config.warn_trailing_ellipsis = true;
+            }
+            "--require-revert-sha" => {
+                parsed.config.require_revert_sha = true;
+            }
+            "--require-release-version" => {
+                parsed.config.require_release_version = true;
+            }
+            "--release-commit" => {
+                parsed.config.release_commit =
+                    parse_release_commit(value_of(&args, index, "release-commit")?)?
+            }
+            "--body-mentions-scope" => {
+                parsed.config.body_mentions_scope = true;
+            }
+            "--fuzzy-type" => {
+                parsed.config.fuzzy_type = true;
+            }
+            "--warn-non-imperative" => {
+                parsed.config.warn_non_imperative = true;
+            }
+            "--non-imperative-suffixes" => {
+                parsed.config.non_imperative_suffixes =
+                    parse_list(value_of(&args, index, "non-imperative-suffixes")?)
+            }
+            "--imperative-exceptions" => {
+                parsed.config.imperative_exceptions =
+                    parse_list(value_of(&args, index, "imperative-exceptions")?)
+            }
+            "--warn-redundant-type" => {
+                parsed.config.warn_redundant_type = true;
+            }
+            "--warn-mixed-scope-separators" => {
+                parsed.config.warn_mixed_scope_separators = true;
+            }
+            "--warn-url-only-description" => {
+                parsed.config.warn_url_only_description = true;
+            }
+            "--ascii-only-subject" => {
+                parsed.config.ascii_only_subject = true;
+            }
+            "--encoding-check" => {
+                parsed.config.encoding_check = true;
+            }
+            "--allowed-verbs" => {
+                parsed.config.allowed_verbs = parse_list(value_of(&args, index, "allowed-verbs")?)
+            }
+            "--require-body-sections" => {
+                parsed.config.required_body_sections =
+                    parse_list(value_of(&args, index, "require-body-sections")?)
+            }
+            "--forbidden-body-words" => {
+                parsed.config.forbidden_body_words =
+                    parse_list(value_of(&args, index, "forbidden-body-words")?)
+            }
+            "--require-footer-blank-line" => {
+                parsed.config.footer_blank_line = true;
+            }
+            "--allowed-footers" => {
+                parsed.config.allowed_footers =
+                    parse_list(value_of(&args, index, "allowed-footers")?)
+            }
+            "--require-capitalized-footer-tokens" => {
+                parsed.config.capitalized_footer_tokens = true;
+            }
+            "--description-pattern" => {
+                let pattern = value_of(&args, index, "description-pattern")?;
+                parsed.config.description_pattern =
+                    Some(Pattern::new(&format!("^(?:{})$", pattern))?)
+            }
+            "--prefix-pattern" => {
+                let pattern = value_of(&args, index, "prefix-pattern")?;
+                parsed.config.prefix_pattern = Some(Pattern::new(&format!("^(?:{})$", pattern))?)
+            }
+            "--placeholder-patterns" => {
+                parsed.config.placeholder_patterns =
+                    parse_list(value_of(&args, index, "placeholder-patterns")?)
+                        .iter()
+                        .map(|pattern| Pattern::new(pattern))
+                        .collect::<Result<_, _>>()?
+            }
+            "--allow-at-in-scope" => {
+                parsed.config.parse_options.allow_at_in_scope = true;
+            }
+            "--scopes-file" => {
+                parsed.config.allowed_scopes =
+                    parse_list_file(&fs::read_to_string(value_of(&args, index, "scopes-file")?)?)
+            }
+            "--scopes-from-codeowners" => {
+                let path = value_of(&args, index, "scopes-from-codeowners")?;
+                for scope in parse_codeowners(&fs::read_to_string(path)?) {
+                    if !parsed.config.allowed_scopes.contains(&scope) {
+                        parsed.config.allowed_scopes.push(scope);
+                    }
                 }
-            },
+            }
+            "--reviewers-file" => {
+                parsed.config.reviewers = parse_list_file(&fs::read_to_string(value_of(
+                    &args,
+                    index,
+                    "reviewers-file",
+                )?)?)
+            }
+            "--deny-scopes" => {
+                parsed.config.deny_scopes = parse_list(value_of(&args, index, "deny-scopes")?)
+            }
+            "--sort-scopes" => {
+                parsed.config.sort_scopes = true;
+            }
+            "--issue-footer-style" => {
+                parsed.config.issue_footer_style = Some(parse_issue_footer_style(value_of(
+                    &args,
+                    index,
+                    "issue-footer-style",
+                )?)?)
+            }
+            "--scope-case" => {
+                parsed.config.scope_case = parse_scope_case(value_of(&args, index, "scope-case")?)?
+            }
+            "--empty-scope-parens" => {
+                parsed.config.empty_scope_parens =
+                    parse_empty_scope_parens(value_of(&args, index, "empty-scope-parens")?)?
+            }
+            "--warn-empty-scope-parens" => {
+                parsed.config.empty_scope_parens = EmptyScopeParens::Warn;
+            }
+            "--require-lowercase-scope-for" => {
+                parsed.config.lowercase_scope_for =
+                    parse_list(value_of(&args, index, "require-lowercase-scope-for")?)
+            }
+            "--subject-case" => {
+                parsed.config.subject_case =
+                    parse_subject_case(value_of(&args, index, "subject-case")?)?
+            }
+            "--max-description-length" => {
+                parsed.config.max_description_length = Some(parse_number(value_of(
+                    &args,
+                    index,
+                    "max-description-length",
+                )?)?)
+            }
+            "--max-consecutive-blank-lines" => {
+                parsed.config.max_consecutive_blank_lines = Some(parse_number(value_of(
+                    &args,
+                    index,
+                    "max-consecutive-blank-lines",
+                )?)?)
+            }
+            "--max-message-bytes" => {
+                parsed.config.max_message_bytes =
+                    Some(parse_number(value_of(&args, index, "max-message-bytes")?)?)
+            }
+            "--length-excludes-ticket" => {
+                parsed.config.length_excludes_ticket = true;
+            }
+            "--max-body-lines" => {
+                parsed.config.max_body_lines =
+                    Some(parse_number(value_of(&args, index, "max-body-lines")?)?)
+            }
+            "--no-scope-for" => no_scope_for = parse_list(value_of(&args, index, "no-scope-for")?),
+            "--require-description-for" => {
+                require_description_for =
+                    parse_list(value_of(&args, index, "require-description-for")?)
+            }
+            "--scope-sets" => {
+                scope_sets.extend(parse_mapping(value_of(&args, index, "scope-sets")?))
+            }
+            "--type-scopes" => {
+                type_scopes.extend(parse_mapping(value_of(&args, index, "type-scopes")?))
+            }
+            "--scope-types" => {
+                parsed.config.scope_types = parse_mapping(value_of(&args, index, "scope-types")?)
+            }
+            "--squash-aware" => {
+                parsed.config.squash_aware = true;
+            }
+            "--input-format" => {
+                parsed.input_format = parse_input_format(value_of(&args, index, "input-format")?)?
+            }
+            "--format" | "-f" => parsed.format = parse_format(value_of(&args, index, "format")?)?,
+            "--disable-rules" => {
+                parsed.config.disabled_rules = parse_list(value_of(&args, index, "disable-rules")?)
+            }
+            "--severity-overrides" => {
+                parsed.config.severity_overrides =
+                    parse_severity_overrides(value_of(&args, index, "severity-overrides")?)?
+            }
+            "--categories" => categories = parse_mapping(value_of(&args, index, "categories")?),
+            "--type-help" => type_help = parse_help(value_of(&args, index, "type-help")?),
+            "--fail-fast" => {
+                parsed.fail_fast = true;
+            }
+            "--output-file" => {
+                parsed.output_file = Some(value_of(&args, index, "output-file")?.to_string())
+            }
+            "--preprocess" => {
+                parsed.preprocess = Some(value_of(&args, index, "preprocess")?.to_string())
+            }
+            "--title" => parsed.title = Some(value_of(&args, index, "title")?.to_string()),
+            "--ignore-file" => {
+                parsed.ignore_file = Some(value_of(&args, index, "ignore-file")?.to_string())
+            }
+            "--baseline" => {
+                parsed.baseline =
+                    parse_list_file(&fs::read_to_string(value_of(&args, index, "baseline")?)?)
+            }
+            "--write-baseline" => {
+                parsed.write_baseline = Some(value_of(&args, index, "write-baseline")?.to_string())
+            }
+            "--report-only" => {
+                parsed.report_only = true;
+            }
+            "--exit-zero" => {
+                parsed.exit_zero = true;
+            }
+            "--null-output" => {
+                parsed.null_output = true;
+            }
+            "--commit-source" => {
+                let source = value_of(&args, index, "commit-source")?;
+                skips_commit_source(source)?;
+                parsed.commit_source = Some(source.to_string())
+            }
+            "--skip-identical" => {
+                parsed.skip_identical = true;
+            }
+            "--diff-suggest" => {
+                parsed.diff_suggest = true;
+            }
+            "--normalize-output" => {
+                parsed.normalize_output = true;
+            }
+            "--template" => parsed.template = Some(value_of(&args, index, "template")?.to_string()),
+            "--list-types" => {
+                parsed.list_types = true;
+            }
+            "--print-schema" => {
+                parsed.print_schema = true;
+            }
+            "--daemon" => {
+                parsed.daemon = true;
+            }
+            "--stats" => {
+                parsed.stats = true;
+            }
+            "--compact" => {
+                parsed.compact = true;
+            }
+            "--parallel" => {
+                parsed.parallel = true;
+            }
+            "--changelog" => {
+                parsed.changelog = true;
+            }
+            "--type-order" => parsed.type_order = parse_list(value_of(&args, index, "type-order")?),
+            "--changelog-titles" => {
+                parsed.changelog_titles = parse_help(value_of(&args, index, "changelog-titles")?)
+            }
             _ => continue,
         }
     }
 
-    Ok((dont_exit_on_errors, ignore_case, commit_types.to_vec()))
+    validate_commit_types(&parsed.config.commit_types)?;
+    // Narrows the configured types, wherever they come from, without having
+    // to repeat their definitions.
+    if let Some(names) = require_type_in {
+        parsed
+            .config
+            .commit_types
+            .retain(|commit_type| names.contains(&commit_type.commit_type));
+    }
+    apply_type_scopes(&mut parsed.config.commit_types, &type_scopes)?;
+    expand_scope_sets(&mut parsed.config.commit_types, &scope_sets)?;
+
+    // Applied once all arguments are read, so it doesn't matter whether the
+    // categories are given before or after `--types`.
+    for (category, types) in categories {
+        for commit_type in parsed.config.commit_types.iter_mut() {
+            if types.contains(&commit_type.commit_type) {
+                commit_type.category = Some(category.to_string());
+            }
+        }
+    }
+    for (name, help) in type_help {
+        for commit_type in parsed.config.commit_types.iter_mut() {
+            if commit_type.commit_type == name {
+                commit_type.help = Some(help.to_string());
+            }
+        }
+    }
+    for commit_type in parsed.config.commit_types.iter_mut() {
+        if no_scope_for.contains(&commit_type.commit_type) {
+            commit_type.required.retain(|field| field != "scope");
+            commit_type.forbidden.push("scope".to_string());
+        }
+        if require_description_for.contains(&commit_type.commit_type)
+            && !commit_type.required.contains(&"description".to_string())
+        {
+            commit_type.required.push("description".to_string());
+        }
+    }
+
+    Ok(parsed)
 }
 
-fn main() {
-    let (dont_exit_on_errors, ignore_case, commit_types) = match parse_args() {
-        Ok(args) => args,
-        Err(err) => {
-            println!("Error!: {:#?}", err);
-            process::exit(1);
+// Batch input is a list of messages separated by NUL bytes, which is what
+// `git log -z --format=%B` produces.
+fn split_batch(text: &str) -> Vec<&str> {
+    text.split('\0')
+        .filter(|message| !message.trim().is_empty())
+        .collect()
+}
+
+// Identifies a violation of a baseline by the message and the diagnostic,
+// hashed with 64 bit FNV-1a, which unlike the hasher of the standard library
+// is the same across Rust versions.
+fn fingerprint(message: &str, diagnostic: &Diagnostic) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in [message.trim(), diagnostic.code, &diagnostic.message] {
+        for byte in part.bytes().chain([0]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
         }
+    }
+    format!("{:016x}", hash)
+}
+
+// With `--fail-fast` the batch stops at the first invalid commit, which is
+// the last report returned.
+fn lint(message: &str, args: &Args) -> Result<AnalysisResult, Error> {
+    let mut report = match args.input_format {
+        InputFormat::Message => analyze(message, &args.config),
+        InputFormat::Fields => analyze_commit(rcop::parse_fields(message)?, &args.config),
     };
+    if !args.baseline.is_empty() {
+        report
+            .diagnostics
+            .retain(|diagnostic| !args.baseline.contains(&fingerprint(message, diagnostic)));
+    }
+    Ok(report)
+}
 
-    let syntax_tree = match parse(stdin()) {
-        Ok(result) => result,
-        Err(err) => {
-            if !dont_exit_on_errors {
-                println!("Error!: {:?}", err);
-                process::exit(1);
+// The baseline keeps the violations it already has, so it can be updated
+// while it is applied.
+fn write_baseline<W: Write>(
+    writer: &mut W,
+    path: &str,
+    messages: &[&str],
+    reports: &[AnalysisResult],
+    baseline: &[String],
+) -> Result<(), Error> {
+    let mut fingerprints = baseline.to_vec();
+    for (message, report) in messages.iter().zip(reports) {
+        for diagnostic in &report.diagnostics {
+            let fingerprint = fingerprint(message, diagnostic);
+            if !fingerprints.contains(&fingerprint) {
+                fingerprints.push(fingerprint);
             }
-            (
-                "".to_string(),
-                "".to_string(),
-                "".to_string(),
-                "".to_string(),
-            )
         }
+    }
+    let mut text = "# rcop baseline, violations that are not reported\n".to_string();
+    for fingerprint in &fingerprints {
+        text.push_str(fingerprint);
+        text.push('\n');
+    }
+    fs::write(path, text)?;
+    writeln!(
+        writer,
+        "rcop: wrote {} violations to the baseline {}",
+        fingerprints.len(),
+        path
+    )?;
+    Ok(())
+}
+
+// Every thread lints a consecutive chunk of the messages, so joining the
+// threads in order keeps the order of the input.
+fn lint_parallel(messages: &[&str], args: &Args) -> Vec<Result<AnalysisResult, Error>> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = messages.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = messages
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|message| lint(message, args))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("linting thread panicked"))
+            .collect()
+    })
+}
+
+// With `--parallel` all messages are linted up front, and the results are
+// then taken just like they would be one by one, so `--fail-fast` and the
+// errors of `--input-format fields` behave the same.
+fn lint_all(messages: &[&str], args: &Args) -> Result<Vec<AnalysisResult>, Error> {
+    let results: Box<dyn Iterator<Item = Result<AnalysisResult, Error>>> = match args.parallel {
+        true => Box::new(lint_parallel(messages, args).into_iter()),
+        false => Box::new(messages.iter().map(|message| lint(message, args))),
     };
-    let validation = match validate(commit_types, ignore_case, &syntax_tree.0, &syntax_tree.1, &syntax_tree.2) {
-        Ok(result) => result,
-        Err(err) => {
-            if !dont_exit_on_errors {
-                println!("Error!: {:#?}", err);
-                process::exit(1);
-            }
-            false
+    let mut reports = vec![];
+    for report in results {
+        let report = report?;
+        let valid = report.valid();
+        reports.push(report);
+        if args.fail_fast && !valid {
+            break;
         }
-    };
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_DEFAULT);
-    table.set_titles(row!["Type", "Scope", "Description", "Body", "Valid"]);
-    table.add_row(row![
-        syntax_tree.0,
-        syntax_tree.1,
-        syntax_tree.2,
-        syntax_tree.3,
-        validation
-    ]);
-    table.printstd();
+    }
+    Ok(reports)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Cursor;
+// Only suggests corrections for the header of invalid commits, fields input
+// has no header to correct.
+fn write_suggestions<W: Write>(
+    writer: &mut W,
+    messages: &[&str],
+    reports: &[AnalysisResult],
+    args: &Args,
+) -> Result<(), Error> {
+    if !args.diff_suggest || args.input_format == InputFormat::Fields {
+        return Ok(());
+    }
+    for (message, report) in messages.iter().zip(reports) {
+        if report.valid() {
+            continue;
+        }
+        let line = message.lines().next().unwrap_or("").trim();
+        if let Some(suggestion) = suggest_header(line, &args.config) {
+            render_diff(writer, line, &suggestion)?;
+        }
+    }
+    Ok(())
+}
 
-    #[test]
-    fn test_parse_valid_input() {
-        let input = b"feat(module): Add a new feature.\nThis is the first line of the feature.\nAnd this is the last line.";
-        let expected_output = (
-            "feat".to_string(),
-            "module".to_string(),
-            "Add a new feature.".to_string(),
-            "This is the first line of the feature.\nAnd this is the last line.".to_string(),
-        );
-        let result = parse(Cursor::new(input)).unwrap();
-        assert_eq!(result, expected_output);
+fn render<W: Write>(
+    writer: &mut W,
+    reports: &[AnalysisResult],
+    batch: bool,
+    args: &Args,
+) -> Result<(), Error> {
+    match args.format {
+        Format::Table => render_table(writer, reports),
+        Format::Github => {
+            for report in reports {
+                render_github(writer, &report.diagnostics)?;
+            }
+            Ok(())
+        }
+        Format::Json => render_json(writer, reports, batch, args.title.as_deref()),
+        Format::Markdown => render_markdown(writer, reports),
+        Format::Toml => render_toml(writer, reports, batch, args.title.as_deref()),
     }
+}
 
-    #[test]
-    fn test_parse_invalid_input_with_fixup() {
-        let input = b"fixup! fix: This is a fixup commit.\nThis is another line of the commit.\nAnd this is the last line.";
-        let expected_output = ErrorKind::InvalidData;
-        let result = parse(Cursor::new(input)).unwrap_err().kind();
-        assert_eq!(result, expected_output);
+fn run<R: Read, W: Write>(mut input: R, args: &Args, writer: &mut W) -> Result<bool, Error> {
+    let mut text = String::new();
+    input
+        .read_to_string(&mut text)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let messages = if args.batch {
+        split_batch(&text)
+    } else {
+        vec![text.as_str()]
+    };
+    let reports = lint_all(&messages, args)?;
+    let validation = reports.iter().all(AnalysisResult::valid);
+    if let Some(path) = &args.write_baseline {
+        write_baseline(writer, path, &messages, &reports, &args.baseline)?;
+        return Ok(true);
     }
 
-    #[test]
-    fn test_parse_invalid_input() {
-        let input = b"This is not a valid input because it does not start with a keyword.";
-        let expected_output = ErrorKind::InvalidData;
-        let result = parse(Cursor::new(input)).unwrap_err().kind();
-        assert_eq!(result, expected_output);
+    // Valid messages are echoed in their canonical form, to be piped into
+    // `git commit -F -`. Invalid ones get the usual report instead.
+    if args.normalize_output && validation {
+        let normalized: Vec<String> = reports
+            .iter()
+            .map(|report| normalize(&report.commit, &args.config))
+            .collect();
+        write!(writer, "{}", normalized.join("\0"))?;
+        return Ok(validation);
     }
 
-    #[test]
-    fn test_parse_input_without_colon() {
-        let input = b"fixup! fix This is a fixup commit.\nThis is another line of the commit.\nAnd this is the last line.";
-        let expected_output = ErrorKind::InvalidData;
-        let result = parse(Cursor::new(input)).unwrap_err().kind();
-        assert_eq!(result, expected_output);
+    // Structured formats carry the title and leave out the suggestions.
+    let structured = matches!(args.format, Format::Json | Format::Toml);
+    if let (Some(title), false) = (&args.title, structured) {
+        writeln!(writer, "{}", title)?;
+    }
+    if args.stats {
+        let mut stats = stats::collect(&reports);
+        stats::sort_by_type(&mut stats.types, &args.type_order, |(commit_type, _)| {
+            commit_type
+        });
+        render_stats(writer, &stats, args.format)?;
+        return Ok(validation);
+    }
+    if args.compact || args.changelog {
+        let headers: Vec<&str> = messages
+            .iter()
+            .map(|message| message.lines().next().unwrap_or("").trim())
+            .collect();
+        match args.changelog {
+            true => render_changelog(
+                writer,
+                &headers,
+                &reports,
+                &args.changelog_titles,
+                &args.type_order,
+            )?,
+            false => render_compact(writer, &headers, &reports)?,
+        }
+        return Ok(validation);
+    }
+    // Every commit is a record of its own, terminated by a NUL byte instead
+    // of a newline, so subjects spanning several lines can't be confused
+    // with the next commit.
+    if args.null_output {
+        for (message, report) in messages.iter().zip(&reports) {
+            let mut record = Vec::new();
+            render(&mut record, slice::from_ref(report), false, args)?;
+            if !structured {
+                write_suggestions(&mut record, &[*message], slice::from_ref(report), args)?;
+            }
+            if record.last() == Some(&b'\n') {
+                record.pop();
+            }
+            record.push(b'\0');
+            writer.write_all(&record)?;
+        }
+        return Ok(validation);
+    }
+    if let (Format::Table, false, Some(error), false) = (
+        args.format,
+        args.batch,
+        reports
+            .first()
+            .and_then(|report| report.errors().into_iter().next()),
+        args.dont_exit_on_errors || args.report_only,
+    ) {
+        writeln!(writer, "Error!: {}", error.message)?;
+        write_suggestions(writer, &messages, &reports, args)?;
+        return Ok(false);
     }
+    render(writer, &reports, args.batch, args)?;
+    if !structured {
+        write_suggestions(writer, &messages, &reports, args)?;
+    }
+    Ok(validation)
+}
 
-    #[test]
-    fn test_parse_input_without_parenthesis() {
-        let input = b"feature module: Add a new feature.\nThis is the first line of the feature.\nAnd this is the last line.";
-        let expected_output = ErrorKind::InvalidData;
-        let result = parse(Cursor::new(input)).unwrap_err().kind();
-        assert_eq!(result, expected_output);
+// Every message and every response is framed by its length in bytes, in
+// decimal on a line of its own, e.g. `8\nfix: foo`. Ends when the input
+// does, so an editor can keep one process running for all its checks.
+fn daemon<R: BufRead, W: Write>(mut input: R, args: &Args, writer: &mut W) -> Result<(), Error> {
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let length: usize = line.trim().parse().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Expected the length of the message, got '{}'", line.trim()),
+            )
+        })?;
+        // The length comes from the client, so it isn't trusted to allocate.
+        let mut message = vec![];
+        input
+            .by_ref()
+            .take(length as u64)
+            .read_to_end(&mut message)?;
+        if message.len() < length {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Expected a message of {} bytes, but the input ended after {}",
+                    length,
+                    message.len()
+                ),
+            ));
+        }
+        // A message that can't be validated is answered with the error, only
+        // broken framing ends the session.
+        let mut response = Vec::new();
+        match String::from_utf8(message)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+            .and_then(|message| lint_all(&[&message], args))
+        {
+            Ok(reports) => render_json(&mut response, &reports, false, args.title.as_deref())?,
+            Err(err) => render_json_error(&mut response, &err)?,
+        }
+        writeln!(writer, "{}", response.len())?;
+        writer.write_all(&response)?;
+        writer.flush()?;
     }
+}
 
-    #[test]
-    fn test_parse_commit_types() {
-        // Test case 1: Check that the function can parse a commit type with no required fields
-        let text = "fix=".to_string();
-        let expected_output = vec![CommitMessage {
-            commit_type: "fix".to_string(),
-            required: vec![],
-        }];
-        assert_eq!(parse_commit_types(text), expected_output);
+// Writes the rendered output to the file, and a one line summary to the
+// writer, so the outcome is still visible in a CI log.
+fn run_to_file<R: Read, W: Write>(
+    input: R,
+    args: &Args,
+    path: &str,
+    writer: &mut W,
+) -> Result<bool, Error> {
+    let mut file = File::create(path)?;
+    let valid = run(input, args, &mut file)?;
+    let outcome = match valid {
+        true => "valid",
+        false => "invalid",
+    };
+    writeln!(writer, "rcop: {}, results written to {}", outcome, path)?;
+    Ok(valid)
+}
 
-        // Test case 2: Check that the function can parse a commit type with required fields
-        let text = "fix=field1,field2".to_string();
-        let expected_output = vec![CommitMessage {
-            commit_type: "fix".to_string(),
-            required: vec!["field1".to_string(), "field2".to_string()],
-        }];
-        assert_eq!(parse_commit_types(text), expected_output);
+// Returns the stream the commit message is read from, which is stdin unless a
+// file was given, or the output of `git log` with `--since`. With
+// `--types-stdin`, stdin holds the types spec instead.
+fn open_input<R: Read + 'static, G: FnOnce(&[String]) -> Result<String, Error>>(
+    mut stdin: R,
+    args: &mut Args,
+    git: G,
+) -> Result<Box<dyn Read>, Error> {
+    if args.types_stdin {
+        if args.file.is_none() && args.since.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "--types-stdin requires the commit messages to be read with --file or --since",
+            ));
+        }
+        let mut text = String::new();
+        stdin.read_to_string(&mut text)?;
+        args.config.commit_types = parse_commit_types(text.trim().to_string());
+        validate_commit_types(&args.config.commit_types)?;
+    }
+    match (&args.file, &args.since) {
+        (Some(_), Some(_)) => Err(Error::new(
+            ErrorKind::InvalidData,
+            "--since can't be combined with --file",
+        )),
+        (Some(path), None) => Ok(Box::new(File::open(path)?)),
+        (None, Some(since)) => Ok(Box::new(Cursor::new(git(&git::log_args(since))?))),
+        (None, None) => Ok(Box::new(stdin)),
+    }
+}
 
-        // Test case 3: Check that the function can parse multiple commit types
-        let text = "fix=field1,field2;feature=field3,field4".to_string();
-        let expected_output = vec![
-            CommitMessage {
-                commit_type: "fix".to_string(),
-                required: vec!["field1".to_string(), "field2".to_string()],
-            },
-            CommitMessage {
-                commit_type: "feature".to_string(),
-                required: vec!["field3".to_string(), "field4".to_string()],
-            },
-        ];
-        assert_eq!(parse_commit_types(text), expected_output);
+// Returns `None` if the message is the one of the previous commit, which
+// happens when amending without rewording. Without a previous commit, e.g.
+// in a new repository, any message counts as changed.
+fn read_unless_identical<R: Read, G: FnOnce(&[String]) -> Result<String, Error>>(
+    mut input: R,
+    git: G,
+) -> Result<Option<String>, Error> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    match git(&git::previous_message_args()) {
+        Ok(previous) if previous.trim() == text.trim() => Ok(None),
+        _ => Ok(Some(text)),
+    }
+}
+
+// Pipes the message through `sh -c COMMAND`, a failing command is an error
+// rather than an empty message.
+fn shell(command: &str, text: &str) -> Result<String, Error> {
+    let mut child = process::Command::new("sh")
+        .args(["-c", command])
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut child_stdin) = child.stdin.take() {
+        child_stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "--preprocess '{}' failed with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+fn preprocess<R: Read, F: FnOnce(&str) -> Result<String, Error>>(
+    mut input: R,
+    filter: F,
+) -> Result<Cursor<String>, Error> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    Ok(Cursor::new(filter(&text)?))
+}
+
+// With `--file`, the file wins over anything piped into stdin, unless stdin
+// holds the types spec.
+fn input_warning(args: &Args, stdin_piped: bool) -> Option<&'static str> {
+    match (&args.file, stdin_piped, args.types_stdin) {
+        (Some(_), true, false) => Some(
+            "Warning: got both --file and input on stdin, the commit message is read from --file",
+        ),
+        _ => None,
+    }
+}
+
+// Only a pipe or a non-empty file counts, so running as a git hook with
+// stdin attached to `/dev/null` or a terminal doesn't warn.
+#[cfg(unix)]
+fn stdin_piped() -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    match fs::metadata("/dev/stdin") {
+        Ok(metadata) => {
+            metadata.file_type().is_fifo() || (metadata.is_file() && metadata.len() > 0)
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn stdin_piped() -> bool {
+    false
+}
+
+// Branches without a ticket, like `main`, aren't checked.
+fn load_branch_ticket<G: FnOnce(&[String]) -> Result<String, Error>>(
+    args: &mut Args,
+    git: G,
+) -> Result<(), Error> {
+    if args.branch_ticket {
+        args.config.branch_ticket = git::ticket(&git(&git::branch_args())?);
+    }
+    Ok(())
+}
+
+// The source `prepare-commit-msg` gets from git. Messages that git or a
+// template wrote aren't the words of the author yet. An empty source means
+// the editor was opened without any message.
+fn skips_commit_source(source: &str) -> Result<bool, Error> {
+    match source {
+        "" | "message" | "commit" => Ok(false),
+        "template" | "merge" | "squash" => Ok(true),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown commit source '{}', expected one of: message, template, merge, squash, commit",
+                source
+            ),
+        )),
+    }
+}
+
+// The message git prepared for the commit, in the git directory of the
+// repository, so it also works in worktrees. A `--file` given as well wins.
+fn load_staged<G: FnOnce(&[String]) -> Result<String, Error>>(
+    args: &mut Args,
+    git: G,
+) -> Result<(), Error> {
+    if args.staged && args.file.is_none() {
+        let git_dir = git(&git::git_dir_args())?;
+        let path = Path::new(git_dir.trim()).join("COMMIT_EDITMSG");
+        args.file = Some(path.to_string_lossy().to_string());
+    }
+    Ok(())
+}
+
+fn write_template<W: Write>(writer: &mut W, config: &Config, name: &str) -> Result<(), Error> {
+    match header::find(&config.commit_types, config.ignore_case, name) {
+        Some(commit_type) => {
+            write!(writer, "{}", header::template(commit_type))?;
+            Ok(())
+        }
+        None => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown commit type '{}', expected one of: {}",
+                name,
+                config
+                    .commit_types
+                    .iter()
+                    .map(|commit_type| commit_type.commit_type.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
+        )),
+    }
+}
+
+// Uses `.rcopignore` in the current directory, unless another file is given.
+fn load_ignore_file(args: &mut Args) -> Result<(), Error> {
+    let path = match &args.ignore_file {
+        Some(path) => path.to_string(),
+        None if Path::new(".rcopignore").exists() => ".rcopignore".to_string(),
+        None => return Ok(()),
+    };
+    args.config.ignore_patterns = ignore::parse(&fs::read_to_string(path)?)?;
+    Ok(())
+}
+
+// `--report-only` never fails, so rcop can be introduced without blocking
+// commits right away. `--exit-zero` leaves the gating to the tool reading
+// the output.
+fn exit_code(args: &Args, valid: bool) -> i32 {
+    if valid || args.dont_exit_on_errors || args.report_only || args.exit_zero {
+        0
+    } else {
+        1
+    }
+}
+
+// Checks the setup in `root` without changing anything. Config files are
+// parsed on their own, so a broken one is reported instead of failing the
+// whole run.
+fn doctor<W: Write>(writer: &mut W, root: &Path, args: &[String]) -> Result<bool, Error> {
+    let mut healthy = true;
+    let hook = root.join(".git").join("hooks").join("commit-msg");
+    if !root.join(".git").is_dir() {
+        healthy = false;
+        writeln!(writer, "commit-msg hook: missing, not a git repository")?;
+    } else {
+        match fs::read_to_string(&hook) {
+            Ok(script) if script.contains("rcop") => writeln!(
+                writer,
+                "commit-msg hook: ok, .git/hooks/commit-msg runs rcop"
+            )?,
+            Ok(_) => {
+                healthy = false;
+                writeln!(
+                    writer,
+                    "commit-msg hook: invalid, .git/hooks/commit-msg doesn't run rcop"
+                )?
+            }
+            Err(_) => {
+                healthy = false;
+                writeln!(
+                    writer,
+                    "commit-msg hook: missing, .git/hooks/commit-msg doesn't exist"
+                )?
+            }
+        }
+    }
+
+    let configs: Vec<&String> = args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| *arg == "--config")
+        .filter_map(|(index, _)| args.get(index + 1))
+        .collect();
+    if configs.is_empty() {
+        writeln!(
+            writer,
+            "config: none, pass one with --config to change the defaults"
+        )?;
+    }
+    for path in configs {
+        match fs::read_to_string(root.join(path)).and_then(|text| profile::parse(&text)) {
+            Ok(_) => writeln!(writer, "config: ok, {}", path)?,
+            Err(err) => {
+                healthy = false;
+                writeln!(writer, "config: invalid, {}: {}", path, err)?
+            }
+        }
+    }
+    if root.join(".rcopignore").exists() {
+        writeln!(writer, "ignore file: ok, .rcopignore")?;
+    }
+    Ok(healthy)
+}
+
+const HOOK_SCRIPT: &str = "#!/bin/sh\n\nrcop --file \"$1\"\n";
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+// `rcop install-hook [--force] [--uninstall]`. Hooks that weren't written
+// by rcop are only replaced or removed with `--force`.
+fn install_hook<W: Write>(writer: &mut W, root: &Path, args: &[String]) -> Result<(), Error> {
+    let force = args.iter().any(|arg| arg == "--force");
+    let uninstall = args.iter().any(|arg| arg == "--uninstall");
+    if !root.join(".git").is_dir() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "Not a git repository, .git doesn't exist",
+        ));
+    }
+    let hooks = root.join(".git").join("hooks");
+    let hook = hooks.join("commit-msg");
+    let existing = fs::read_to_string(&hook).ok();
+    let ours = existing.as_deref() == Some(HOOK_SCRIPT);
+    if uninstall {
+        match existing {
+            None => writeln!(writer, "No commit-msg hook installed")?,
+            Some(_) if !ours && !force => {
+                return Err(Error::new(
+                    ErrorKind::AlreadyExists,
+                    "The commit-msg hook wasn't installed by rcop, pass --force to remove it",
+                ))
+            }
+            Some(_) => {
+                fs::remove_file(&hook)?;
+                writeln!(writer, "Removed .git/hooks/commit-msg")?;
+            }
+        }
+        return Ok(());
+    }
+    if existing.is_some() && !ours && !force {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            "A commit-msg hook exists already, pass --force to overwrite it",
+        ));
+    }
+    fs::create_dir_all(&hooks)?;
+    fs::write(&hook, HOOK_SCRIPT)?;
+    make_executable(&hook)?;
+    writeln!(writer, "Installed .git/hooks/commit-msg")
+}
+
+fn main() {
+    let env_args: Vec<String> = env::args().collect();
+    if env_args.get(1).map(String::as_str) == Some("install-hook") {
+        if let Err(err) = install_hook(&mut stdout(), Path::new("."), &env_args[2..]) {
+            println!("Error!: {}", err);
+            process::exit(1);
+        }
+        process::exit(0);
+    }
+    if env_args.iter().any(|arg| arg == "--doctor") {
+        match doctor(&mut stdout(), Path::new("."), &env_args) {
+            Ok(healthy) => process::exit(if healthy { 0 } else { 1 }),
+            Err(err) => {
+                println!("Error!: {:?}", err);
+                process::exit(1);
+            }
+        }
+    }
+    let mut args = match parse_args(env_args) {
+        Ok(args) => args,
+        Err(err) => {
+            println!("Error!: {:#?}", err);
+            process::exit(1);
+        }
+    };
+    if let Some(name) = &args.template {
+        match write_template(&mut stdout(), &args.config, name) {
+            Ok(()) => process::exit(0),
+            Err(err) => {
+                println!("Error!: {:?}", err);
+                process::exit(1);
+            }
+        }
+    }
+    if args.print_schema {
+        println!("{}", profile::schema());
+        process::exit(0);
+    }
+    if args.list_types {
+        match render_types(&mut stdout(), &args.config.commit_types, args.format) {
+            Ok(()) => process::exit(0),
+            Err(err) => {
+                println!("Error!: {:?}", err);
+                process::exit(1);
+            }
+        }
+    }
+    if let Err(err) = load_branch_ticket(&mut args, git::run) {
+        println!("Error!: {:#?}", err);
+        process::exit(1);
+    }
+    if let Err(err) = load_staged(&mut args, git::run) {
+        println!("Error!: {:#?}", err);
+        process::exit(1);
+    }
+    if let Err(err) = load_ignore_file(&mut args) {
+        println!("Error!: {:#?}", err);
+        process::exit(1);
+    }
+    if let Some(source) = &args.commit_source {
+        if skips_commit_source(source).unwrap_or(false) {
+            println!("rcop: skipped, the message comes from a {}", source);
+            process::exit(0);
+        }
+    }
+    if args.daemon {
+        match daemon(stdin().lock(), &args, &mut stdout()) {
+            Ok(()) => process::exit(0),
+            Err(err) => {
+                println!("Error!: {:?}", err);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(warning) = input_warning(&args, stdin_piped()) {
+        eprintln!("{}", warning);
+    }
+    let input = match open_input(stdin(), &mut args, git::run) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Error!: {:#?}", err);
+            process::exit(1);
+        }
+    };
+
+    let input: Box<dyn Read> = match args.skip_identical {
+        true => match read_unless_identical(input, git::run) {
+            Ok(Some(text)) => Box::new(Cursor::new(text)),
+            Ok(None) => {
+                println!("rcop: unchanged, the message is the one of the previous commit");
+                process::exit(0);
+            }
+            Err(err) => {
+                println!("Error!: {:#?}", err);
+                process::exit(1);
+            }
+        },
+        false => input,
+    };
+    let input: Box<dyn Read> = match &args.preprocess {
+        Some(command) => match preprocess(input, |text| shell(command, text)) {
+            Ok(text) => Box::new(text),
+            Err(err) => {
+                println!("Error!: {:#?}", err);
+                process::exit(1);
+            }
+        },
+        None => input,
+    };
+
+    let result = match &args.output_file {
+        Some(path) => run_to_file(input, &args, path, &mut stdout()),
+        None => run(input, &args, &mut stdout()),
+    };
+    match result {
+        Ok(valid) => process::exit(exit_code(&args, valid)),
+        Err(err) => {
+            println!("Error!: {:?}", err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcop::{config::ScopeCase, diagnostic::Severity, Commit};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_commit_types() {
+        // Test case 1: Check that the function can parse a commit type with no required fields
+        let text = "fix=".to_string();
+        let expected_output = vec![CommitMessage {
+            commit_type: "fix".to_string(),
+            required: vec![],
+            ..Default::default()
+        }];
+        assert_eq!(parse_commit_types(text), expected_output);
+
+        // Test case 2: Check that the function can parse a commit type with required fields
+        let text = "fix=field1,field2".to_string();
+        let expected_output = vec![CommitMessage {
+            commit_type: "fix".to_string(),
+            required: vec!["field1".to_string(), "field2".to_string()],
+            ..Default::default()
+        }];
+        assert_eq!(parse_commit_types(text), expected_output);
+
+        // Test case 3: Check that the function can parse multiple commit types
+        let text = "fix=field1,field2;feature=field3,field4".to_string();
+        let expected_output = vec![
+            CommitMessage {
+                commit_type: "fix".to_string(),
+                required: vec!["field1".to_string(), "field2".to_string()],
+                ..Default::default()
+            },
+            CommitMessage {
+                commit_type: "feature".to_string(),
+                required: vec!["field3".to_string(), "field4".to_string()],
+                ..Default::default()
+            },
+        ];
+        assert_eq!(parse_commit_types(text), expected_output);
 
         // Test case 4: Check that the function can handle empty input
         let text = "".to_string();
         let expected_output = vec![];
         assert_eq!(parse_commit_types(text), expected_output);
     }
+
+    #[test]
+    fn test_parse_commit_types_scopes() {
+        assert_eq!(
+            parse_commit_types("feat=scope:ui|".to_string())[0].scopes,
+            vec!["ui".to_string()]
+        );
+        assert_eq!(
+            parse_commit_types("feat=scope:ui|web,description;docs=description".to_string()),
+            vec![
+                CommitMessage {
+                    commit_type: "feat".to_string(),
+                    required: vec!["scope".to_string(), "description".to_string()],
+                    scopes: vec!["ui".to_string(), "web".to_string()],
+                    ..Default::default()
+                },
+                CommitMessage {
+                    commit_type: "docs".to_string(),
+                    required: vec!["description".to_string()],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_commit_types_min_description_length() {
+        let commit_types =
+            parse_commit_types("feat=scope,description>=10;fix=description".to_string());
+        assert_eq!(
+            commit_types[0],
+            CommitMessage {
+                commit_type: "feat".to_string(),
+                required: vec!["scope".to_string(), "description".to_string()],
+                min_description_length: Some(10),
+                ..Default::default()
+            }
+        );
+        assert_eq!(commit_types[1].min_description_length, None);
+        assert!(validate_commit_types(&commit_types).is_ok());
+
+        let result = parse_args(args(&["rcop", "--types", "feat=description>=ten"])).unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "Invalid types config, expected a number in 'description>=ten' of the commit type 'feat'"
+        );
+    }
+
+    #[test]
+    fn test_type_scopes() {
+        let config = parse_args(args(&[
+            "rcop",
+            "--types",
+            "feat=scope:ui,description;ci=description;docs=description",
+            "--scope-sets",
+            "docs=readme,guide",
+            "--type-scopes",
+            "ci=github,gitlab;feat=*;docs=$docs",
+        ]))
+        .unwrap()
+        .config;
+        let cells = vec![
+            ("ci(github): cache deps", true),
+            ("ci(gitlab): cache deps", true),
+            ("ci: cache deps", true),
+            ("ci(jenkins): cache deps", false),
+            ("feat(api): add endpoint", true),
+            ("feat(anything): add endpoint", true),
+            ("docs(readme): typo", true),
+            ("docs(github): typo", false),
+        ];
+        for (message, valid) in cells {
+            assert_eq!(
+                analyze(message, &config).valid(),
+                valid,
+                "Unexpected result for '{}'",
+                message
+            );
+        }
+        assert_eq!(
+            analyze("ci(jenkins): cache deps", &config).errors()[0].message,
+            "Scope 'jenkins' is not allowed for the commit type 'ci', expected one of: github, gitlab"
+        );
+        let error = parse_args(args(&["rcop", "--type-scopes", "wip=api"]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Invalid type-scopes config, the commit type 'wip' is not defined"
+        );
+    }
+
+    #[test]
+    fn test_expand_scope_sets() {
+        let mut commit_types =
+            parse_commit_types("feat=scope:$frontend|docs;fix=scope:$backend".to_string());
+        let scope_sets = parse_mapping("frontend=ui,web,mobile;backend=api");
+        expand_scope_sets(&mut commit_types, &scope_sets).unwrap();
+        assert_eq!(commit_types[0].scopes, vec!["ui", "web", "mobile", "docs"]);
+        assert_eq!(commit_types[1].scopes, vec!["api"]);
+
+        let mut commit_types = parse_commit_types("feat=scope:$frontend".to_string());
+        let error = expand_scope_sets(&mut commit_types, &[]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "Invalid types config, the commit type 'feat' references the undefined scope set '$frontend'"
+        );
+
+        let error = parse_args(args(&[
+            "rcop",
+            "--types",
+            "feat=scope:$fe",
+            "--scope-sets",
+            "fe=",
+        ]))
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "Invalid types config, the commit type 'feat' allows no scope, expected at least one after 'scope:'"
+        );
+    }
+
+    #[test]
+    fn test_scope_sets() {
+        let config = parse_args(args(&[
+            "rcop",
+            "--types",
+            "feat=scope:$frontend,description",
+            "--scope-sets",
+            "frontend=ui,web,mobile",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze("feat(web): add page", &config).valid());
+        assert!(analyze("feat(ui,web): add page", &config).valid());
+        let report = analyze("feat(api): add page", &config);
+        assert_eq!(report.errors()[0].code, "scope-enum");
+        assert_eq!(
+            report.errors()[0].message,
+            "Scope 'api' is not allowed for the commit type 'feat', expected one of: ui, web, mobile"
+        );
+        assert!(parse_args(args(&["rcop", "--types", "feat=scope:$frontend"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_commit_types() {
+        let test_cases = vec![
+            ("feat;;fix", "has no name"),
+            ("feat;", "has no name"),
+            ("=scope", "has no name"),
+            ("feat=;feat=", "'feat' is defined more than once"),
+            (
+                "feat=scope;fix=;feat=description",
+                "'feat' is defined more than once",
+            ),
+            ("feat=scope:", "'feat' allows no scope"),
+            ("feat=scope:|,description", "'feat' allows no scope"),
+        ];
+        for (types, expected) in test_cases {
+            let result = parse_args(args(&["rcop", "--types", types])).unwrap_err();
+            assert_eq!(result.kind(), ErrorKind::InvalidData);
+            assert!(
+                result.to_string().contains(expected),
+                "Unexpected error for '{}': {}",
+                types,
+                result
+            );
+        }
+
+        assert!(validate_commit_types(&parse_commit_types("feat=;fix=".to_string())).is_ok());
+        assert!(validate_commit_types(&[]).is_ok());
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(args(&["rcop", "-e", "--format", "github", "-t", "fix="])).unwrap();
+        assert_eq!(
+            parsed,
+            Args {
+                dont_exit_on_errors: true,
+                format: Format::Github,
+                config: Config {
+                    commit_types: parse_commit_types("fix=".to_string()),
+                    ..Config::default()
+                },
+                ..Args::default()
+            }
+        );
+
+        let result = parse_args(args(&["rcop", "--format"])).unwrap_err().kind();
+        assert_eq!(result, ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_run_github_format() {
+        let parsed = parse_args(args(&["rcop", "--format", "github"])).unwrap();
+
+        let mut output = Vec::new();
+        let valid = run(Cursor::new(b"wild: not a known type"), &parsed, &mut output).unwrap();
+        assert!(!valid);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "::error::Commit type not allowed\n"
+        );
+
+        let mut output = Vec::new();
+        let valid = run(Cursor::new(b"docs: all fine"), &parsed, &mut output).unwrap();
+        assert!(valid);
+        assert!(output.is_empty());
+
+        let mut output = Vec::new();
+        let valid = run(Cursor::new(b"docs: all fine."), &parsed, &mut output).unwrap();
+        assert!(valid);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "::warning::Description should not end with a period\n"
+        );
+    }
+
+    #[test]
+    fn test_disable_rules() {
+        let config = Config::default();
+        let report = analyze("fix(api): msg.", &config);
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["trailing-period"]);
+
+        let config = parse_args(args(&["rcop", "--disable-rules", "trailing-period"]))
+            .unwrap()
+            .config;
+        let report = analyze("fix(api): msg.", &config);
+        assert!(report.diagnostics.is_empty());
+
+        let report = analyze("fix: msg.", &config);
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["scope-required"]);
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let report = analyze("fix(api): msg.", &Config::default());
+        assert!(report.valid());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--severity-overrides",
+            "trailing-period=error",
+        ]))
+        .unwrap()
+        .config;
+        let report = analyze("fix(api): msg.", &config);
+        assert!(!report.valid());
+        assert_eq!(report.errors()[0].code, "trailing-period");
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--severity-overrides",
+            "trailing-period=off,scope-required=warning",
+        ]))
+        .unwrap()
+        .config;
+        let report = analyze("fix: msg.", &config);
+        assert!(report.valid());
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["scope-required"]);
+
+        assert!(parse_args(args(&["rcop", "--severity-overrides", "trailing-period"])).is_err());
+    }
+
+    #[test]
+    fn test_squash_aware() {
+        let input = "Update the login page (#42)\n\n* feat(auth): add remember me\n* fix(auth): typo\n\nfeat(auth): add remember me\nKeeps the session for 30 days";
+
+        let report = analyze(input, &Config::default());
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["header-format"]);
+
+        let config = parse_args(args(&["rcop", "--squash-aware"]))
+            .unwrap()
+            .config;
+        let AnalysisResult {
+            commit,
+            diagnostics,
+            ..
+        } = analyze(input, &config);
+        assert_eq!(
+            commit,
+            Commit {
+                commit_type: "feat".to_string(),
+                scope: "auth".to_string(),
+                description: "add remember me".to_string(),
+                body: "Keeps the session for 30 days".to_string(),
+                footers: vec![],
+                breaking: false,
+            }
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "squash-header");
+        assert!(diagnostics[0].message.contains("line 6"));
+    }
+
+    #[test]
+    fn test_input_format_fields() {
+        let parsed = parse_args(args(&["rcop", "--input-format", "fields", "-f", "json"])).unwrap();
+        let mut output = Vec::new();
+        let input = r#"{"type": "feat", "scope": "", "description": "add: endpoint", "body": ""}"#;
+        assert!(!run(Cursor::new(input), &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#""description":"add: endpoint""#));
+        assert!(output.contains(r#""rule":"scope-required""#));
+
+        let mut output = Vec::new();
+        let input = r#"{"type": "feat", "scope": "api", "description": "add endpoint"}"#;
+        assert!(run(Cursor::new(input), &parsed, &mut output).unwrap());
+
+        let mut output = Vec::new();
+        assert!(run(Cursor::new("feat(api): add endpoint"), &parsed, &mut output).is_err());
+        assert!(parse_args(args(&["rcop", "--input-format", "yaml"])).is_err());
+    }
+
+    #[test]
+    fn test_max_consecutive_blank_lines() {
+        let config = parse_args(args(&["rcop", "--max-consecutive-blank-lines", "1"]))
+            .unwrap()
+            .config;
+        let report = analyze("docs: x\n\nfirst\n\n\n\nsecond", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "body-blank-lines");
+        assert!(analyze("docs: x\n\nfirst\n\nsecond", &config)
+            .diagnostics
+            .is_empty());
+        assert!(
+            analyze("docs: x\n\nfirst\n\n\n\nsecond", &Config::default())
+                .diagnostics
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_max_message_bytes() {
+        let config = parse_args(args(&["rcop", "--max-message-bytes", "14"]))
+            .unwrap()
+            .config;
+        // 8 bytes of header, as 'ä' takes two, two newlines and 4 bytes of body
+        assert!(analyze("docs: ä\n\nbody", &config).valid());
+        let report = analyze("docs: ä\n\nbody!", &config);
+        assert_eq!(report.errors()[0].code, "max-message-bytes");
+        assert!(parse_args(args(&["rcop", "--max-message-bytes", "many"])).is_err());
+    }
+
+    #[test]
+    fn test_length_excludes_ticket() {
+        let message = "fix(auth): JIRA-123 handle the login timeout";
+        let config = parse_args(args(&["rcop", "--max-description-length", "25"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze(message, &config).errors()[0].code,
+            "max-description-length"
+        );
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--max-description-length",
+            "25",
+            "--length-excludes-ticket",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze(message, &config).valid());
+        assert!(!analyze(
+            "fix(auth): JIRA-123 handle the login timeout again",
+            &config
+        )
+        .valid());
+        assert!(!analyze("fix(auth): handle the login timeout again", &config).valid());
+    }
+
+    #[test]
+    fn test_max_body_lines() {
+        let config = parse_args(args(&["rcop", "--max-body-lines", "2"]))
+            .unwrap()
+            .config;
+        assert!(analyze("docs: x\n\none\ntwo\n\n", &config).valid());
+        assert_eq!(
+            analyze("docs: x\n\none\ntwo\nthree", &config).errors()[0].code,
+            "max-body-lines"
+        );
+    }
+
+    #[test]
+    fn test_diff_suggest() {
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--diff-suggest",
+            "--types",
+            "fix=description",
+        ]))
+        .unwrap();
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new("Fix: message."), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Error!: Commit type not allowed\n--- original\n+++ suggested\n-Fix: message.\n+fix: message\n"
+        );
+
+        let mut output = Vec::new();
+        let parsed = parse_args(args(&["rcop", "--diff-suggest", "-e"])).unwrap();
+        assert!(run(Cursor::new("docs: typo."), &parsed, &mut output).unwrap());
+        assert!(!String::from_utf8(output).unwrap().contains("+++ suggested"));
+    }
+
+    #[test]
+    fn test_normalize_output() {
+        let parsed = parse_args(args(&["rcop", "--normalize-output"])).unwrap();
+        let mut output = Vec::new();
+        let input = Cursor::new("docs:  update the readme \n\n\nMore details.  \n\n\n");
+        assert!(run(input, &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "docs: update the readme\n\nMore details.\n"
+        );
+
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new("feat: missing scope"), &parsed, &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().starts_with("Error!:"));
+    }
+
+    #[test]
+    fn test_type_help() {
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--type-help",
+            "docs=Documentation only, no code;feat=A new feature",
+        ]))
+        .unwrap();
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new("wip: message"), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Error!: Commit type not allowed, expected one of:\n  feat: A new feature\n  docs: Documentation only, no code\n"
+        );
+    }
+
+    #[test]
+    fn test_daemon() {
+        let parsed = parse_args(args(&["rcop", "--daemon"])).unwrap();
+        assert!(parsed.daemon);
+        let mut output = Vec::new();
+        let input: &[u8] = b"12\nfix(ui): x\n\n3\n\xff\xfe\xfd12\nwip: message";
+        daemon(input, &parsed, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let mut rest = output.as_str();
+        let mut responses = vec![];
+        while let Some((length, tail)) = rest.split_once('\n') {
+            let (response, tail) = tail.split_at(length.parse().unwrap());
+            responses.push(response);
+            rest = tail;
+        }
+        assert_eq!(responses.len(), 3);
+        assert!(responses[0].contains(r#""description":"x""#));
+        assert!(responses[0].contains(r#""valid":true"#));
+        assert!(responses[1].starts_with(r#"{"error":"invalid utf-8"#));
+        assert!(responses[2].contains(r#""rule":"type-enum""#));
+
+        let parsed = parse_args(args(&["rcop", "--daemon", "--input-format", "fields"])).unwrap();
+        let mut output = Vec::new();
+        daemon(
+            Cursor::new("5\n{\"a\":15\n{\"type\":\"docs\"}"),
+            &parsed,
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches(r#"{"error":"#).count(), 1);
+        assert!(output.contains(r#""type":"docs""#));
+
+        let result = daemon(Cursor::new("x\nfix: x"), &parsed, &mut Vec::new()).unwrap_err();
+        assert_eq!(result.kind(), ErrorKind::InvalidData);
+        for input in ["18446744073709551615\nfix: x", "12\nfix: x"] {
+            let result = daemon(Cursor::new(input), &parsed, &mut Vec::new()).unwrap_err();
+            assert_eq!(result.kind(), ErrorKind::InvalidData);
+            assert!(result.to_string().ends_with("the input ended after 6"));
+        }
+    }
+
+    #[test]
+    fn test_doctor() {
+        let root = env::temp_dir().join("rcop-test-doctor");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".git").join("hooks")).unwrap();
+        fs::write(root.join("rcop.conf"), "ignore-case = true\n").unwrap();
+
+        let config = args(&["rcop", "--doctor", "--config", "rcop.conf"]);
+        let mut output = Vec::new();
+        assert!(!doctor(&mut output, &root, &config).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "commit-msg hook: missing, .git/hooks/commit-msg doesn't exist\nconfig: ok, rcop.conf\n"
+        );
+
+        let hook = root.join(".git").join("hooks").join("commit-msg");
+        fs::write(&hook, "#!/bin/sh\nrcop --file \"$1\"\n").unwrap();
+        let mut output = Vec::new();
+        assert!(doctor(&mut output, &root, &config).unwrap());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("commit-msg hook: ok"));
+
+        fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+        fs::write(root.join("rcop.conf"), "colour = blue\n").unwrap();
+        let mut output = Vec::new();
+        assert!(!doctor(&mut output, &root, &args(&["rcop", "--doctor"])).unwrap());
+        let output_text = String::from_utf8(output).unwrap();
+        assert!(output_text.contains("doesn't run rcop"));
+        assert!(output_text.contains("config: none"));
+        let mut output = Vec::new();
+        assert!(!doctor(&mut output, &root, &config).unwrap());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("config: invalid, rcop.conf"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_install_hook() {
+        let root = env::temp_dir().join("rcop-test-install-hook");
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(
+            install_hook(&mut Vec::new(), &root, &[])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+        fs::create_dir_all(root.join(".git")).unwrap();
+
+        let hook = root.join(".git").join("hooks").join("commit-msg");
+        let mut output = Vec::new();
+        install_hook(&mut output, &root, &[]).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Installed .git/hooks/commit-msg\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&hook).unwrap(),
+            "#!/bin/sh\n\nrcop --file \"$1\"\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                fs::metadata(&hook).unwrap().permissions().mode() & 0o111,
+                0o111
+            );
+        }
+        // Installing again keeps the hook rcop wrote.
+        install_hook(&mut Vec::new(), &root, &[]).unwrap();
+
+        fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+        assert_eq!(
+            install_hook(&mut Vec::new(), &root, &[])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::AlreadyExists
+        );
+        let uninstall = args(&["--uninstall"]);
+        assert_eq!(
+            install_hook(&mut Vec::new(), &root, &uninstall)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert_eq!(fs::read_to_string(&hook).unwrap(), "#!/bin/sh\nexit 0\n");
+        install_hook(&mut Vec::new(), &root, &args(&["--force"])).unwrap();
+        assert_eq!(fs::read_to_string(&hook).unwrap(), HOOK_SCRIPT);
+
+        let mut output = Vec::new();
+        install_hook(&mut output, &root, &uninstall).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Removed .git/hooks/commit-msg\n"
+        );
+        assert!(!hook.exists());
+        let mut output = Vec::new();
+        install_hook(&mut output, &root, &uninstall).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "No commit-msg hook installed\n"
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_null_output() {
+        let parsed =
+            parse_args(args(&["rcop", "-b", "--null-output", "--format", "json"])).unwrap();
+        let mut output = Vec::new();
+        let input = Cursor::new("fix(ui): x\n\nline one\nline two\0wip: y\0");
+        assert!(!run(input, &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        let records: Vec<&str> = output.split_terminator('\0').collect();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].starts_with(r#"{"type":"fix""#));
+        assert!(records[0].ends_with('}'));
+        assert!(records[1].starts_with(r#"{"type":"wip""#));
+        assert!(output.ends_with("}\0"));
+
+        let parsed = parse_args(args(&["rcop", "-b", "--null-output", "-f", "github"])).unwrap();
+        let mut output = Vec::new();
+        run(Cursor::new("wip: y\0fix(ui): x\0"), &parsed, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches('\0').count(), 2);
+        assert!(output.ends_with("\0\0"));
+    }
+
+    #[test]
+    fn test_compact() {
+        let parsed = parse_args(args(&["rcop", "--compact"])).unwrap();
+        let mut output = Vec::new();
+        assert!(run(
+            Cursor::new("feat(api): add thing\n\nDetails"),
+            &parsed,
+            &mut output
+        )
+        .unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "✓ feat(api): add thing\n"
+        );
+
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new("feat: add thing"), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "✗ feat: add thing - Commit type requires a scope, but none given\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_format() {
+        let parsed = parse_args(args(&["rcop", "--format", "toml", "--title", "Check"])).unwrap();
+        let mut output = Vec::new();
+        assert!(run(Cursor::new("feat(api): add endpoint"), &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("title = \"Check\"\n"));
+        assert!(output.lines().any(|line| line == "type = \"feat\""));
+    }
+
+    #[test]
+    fn test_template() {
+        let parsed = parse_args(args(&["rcop", "--template", "feat"])).unwrap();
+        let mut output = Vec::new();
+        write_template(
+            &mut output,
+            &parsed.config,
+            parsed.template.as_ref().unwrap(),
+        )
+        .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("feat(<scope>): <description>"));
+
+        let mut output = Vec::new();
+        write_template(&mut output, &parsed.config, "chore").unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("<scope>"));
+
+        let error = write_template(&mut Vec::new(), &parsed.config, "wip").unwrap_err();
+        assert!(error.to_string().starts_with("Unknown commit type 'wip'"));
+    }
+
+    #[test]
+    fn test_list_types() {
+        let parsed = parse_args(args(&["rcop", "--list-types"])).unwrap();
+        assert!(parsed.list_types);
+        let mut output = Vec::new();
+        render_types(&mut output, &parsed.config.commit_types, parsed.format).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        for commit_type in [
+            "feat", "fix", "build", "chore", "ci", "docs", "perf", "refactor", "revert", "style",
+            "test",
+        ] {
+            assert!(
+                output.contains(&format!("| {} ", commit_type)),
+                "Expected '{}' in\n{}",
+                commit_type,
+                output
+            );
+        }
+        assert!(output.contains("scope, description"));
+    }
+
+    #[test]
+    fn test_staged() {
+        let git_dir = env::temp_dir().join("rcop-test-staged");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::write(git_dir.join("COMMIT_EDITMSG"), "nope: x\n").unwrap();
+
+        let mut parsed = parse_args(args(&["rcop", "--staged"])).unwrap();
+        load_staged(&mut parsed, |git_args| {
+            assert_eq!(git_args, ["rev-parse", "--git-dir"]);
+            Ok(format!("{}\n", git_dir.to_str().unwrap()))
+        })
+        .unwrap();
+        assert_eq!(
+            parsed.file,
+            Some(git_dir.join("COMMIT_EDITMSG").to_str().unwrap().to_string())
+        );
+        let input = open_input(Cursor::new(""), &mut parsed, |_| unreachable!()).unwrap();
+        let mut output = Vec::new();
+        assert!(!run(input, &parsed, &mut output).unwrap());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("Error!: Commit type not allowed"));
+
+        let mut parsed = parse_args(args(&["rcop", "--staged", "--file", "message.txt"])).unwrap();
+        load_staged(&mut parsed, |_| panic!("git should not be called")).unwrap();
+        assert_eq!(parsed.file, Some("message.txt".to_string()));
+        fs::remove_dir_all(&git_dir).unwrap();
+    }
+
+    #[test]
+    fn test_branch_ticket() {
+        let mut parsed = parse_args(args(&["rcop", "--branch-ticket"])).unwrap();
+        load_branch_ticket(&mut parsed, |git_args| {
+            assert_eq!(git_args, git::branch_args());
+            Ok("feature/API-123\n".to_string())
+        })
+        .unwrap();
+        assert_eq!(parsed.config.branch_ticket, Some("API-123".to_string()));
+        assert!(analyze("docs: API-123 describe the endpoint", &parsed.config).valid());
+        assert!(analyze(
+            "docs: describe the endpoint\n\nRefs: API-123",
+            &parsed.config
+        )
+        .valid());
+        assert_eq!(
+            analyze("docs: describe the endpoint", &parsed.config).errors()[0].code,
+            "branch-ticket"
+        );
+
+        let mut parsed = parse_args(args(&["rcop", "--branch-ticket"])).unwrap();
+        load_branch_ticket(&mut parsed, |_| Ok("main\n".to_string())).unwrap();
+        assert!(analyze("docs: describe the endpoint", &parsed.config).valid());
+
+        let mut parsed = parse_args(args(&["rcop"])).unwrap();
+        load_branch_ticket(&mut parsed, |_| panic!("git should not be called")).unwrap();
+        assert_eq!(parsed.config.branch_ticket, None);
+    }
+
+    #[test]
+    fn test_commit_source() {
+        for (source, skipped) in [
+            ("message", false),
+            ("commit", false),
+            ("", false),
+            ("template", true),
+            ("merge", true),
+            ("squash", true),
+        ] {
+            assert_eq!(
+                skips_commit_source(source).unwrap(),
+                skipped,
+                "Unexpected result for '{}'",
+                source
+            );
+            let parsed = parse_args(args(&["rcop", "--commit-source", source])).unwrap();
+            assert_eq!(parsed.commit_source, Some(source.to_string()));
+        }
+        let error = parse_args(args(&["rcop", "--commit-source", "rebase"]))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_skip_identical() {
+        let parsed = parse_args(args(&["rcop", "--skip-identical"])).unwrap();
+        assert!(parsed.skip_identical);
+        let message = "feat(api): add endpoint\n\nDetails\n";
+        let text = read_unless_identical(Cursor::new(message), |git_args| {
+            assert_eq!(git_args, git::previous_message_args());
+            Ok(format!("{}\n", message))
+        })
+        .unwrap();
+        assert_eq!(text, None);
+
+        let text = read_unless_identical(Cursor::new(message), |_| {
+            Ok("feat(api): add other endpoint\n".to_string())
+        })
+        .unwrap();
+        assert_eq!(text.as_deref(), Some(message));
+        let text = read_unless_identical(Cursor::new(message), |_| {
+            Err(Error::other(
+                "fatal: your current branch does not have any commits yet",
+            ))
+        })
+        .unwrap();
+        assert_eq!(text.as_deref(), Some(message));
+    }
+
+    #[test]
+    fn test_profile() {
+        let path = env::temp_dir().join("rcop-test-profile.conf");
+        fs::write(
+            &path,
+            "types = feat=description;fix=description\n\n[profile.strict]\nscope-case = kebab\nmax-description-length = 10\n\n[profile.relaxed]\nignore-case = true\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let config = parse_args(args(&["rcop", "--config", path, "--profile", "strict"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            config.commit_types,
+            parse_commit_types("feat=description;fix=description".to_string())
+        );
+        assert_eq!(config.scope_case, ScopeCase::Kebab);
+        assert_eq!(config.max_description_length, Some(10));
+        assert!(!config.ignore_case);
+
+        let config = parse_args(args(&["rcop", "--profile", "relaxed", "--config", path]))
+            .unwrap()
+            .config;
+        assert_eq!(config.scope_case, ScopeCase::Any);
+        assert!(config.ignore_case);
+        assert!(analyze("FEAT: x", &config).valid());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--config",
+            path,
+            "--profile",
+            "strict",
+            "--max-description-length",
+            "20",
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(config.max_description_length, Some(20));
+
+        assert!(parse_args(args(&["rcop", "--config", path, "--profile", "other"])).is_err());
+        assert!(parse_args(args(&["rcop", "--profile", "strict"])).is_err());
+    }
+
+    #[test]
+    fn test_layered_config_files() {
+        let base = env::temp_dir().join("rcop-test-layered-base.conf");
+        let repo = env::temp_dir().join("rcop-test-layered-repo.conf");
+        fs::write(
+            &base,
+            "types = feat=scope,description;fix=scope,description\nmax-description-length = 50\n",
+        )
+        .unwrap();
+        fs::write(
+            &repo,
+            "types = fix=description\nmax-description-length = 72\n",
+        )
+        .unwrap();
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--config",
+            base.to_str().unwrap(),
+            "--config",
+            repo.to_str().unwrap(),
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(
+            config.commit_types,
+            parse_commit_types("feat=scope,description;fix=description".to_string())
+        );
+        assert_eq!(config.max_description_length, Some(72));
+        assert!(analyze("fix: no scope needed", &config).valid());
+        assert!(!analyze("feat: scope needed", &config).valid());
+    }
+
+    #[test]
+    fn test_bare_breaking_type() {
+        let config = Config::default();
+        let report = analyze("build!", &config);
+        assert_eq!(report.errors()[0].code, "header-format");
+        assert!(report.errors()[0].message.contains("no colon after it"));
+
+        let report = analyze("build!:", &config);
+        assert!(report.commit.breaking);
+        assert_eq!(report.errors()[0].code, "description-required");
+    }
+
+    #[test]
+    fn test_strict_separator_spacing() {
+        let config = parse_args(args(&["rcop", "--strict-separator-spacing"]))
+            .unwrap()
+            .config;
+        assert!(analyze("docs: x", &config).valid());
+        for message in ["docs:x", "docs:  x"] {
+            assert_eq!(
+                analyze(message, &config).errors()[0].code,
+                "separator-spacing"
+            );
+            assert!(analyze(message, &Config::default()).valid());
+        }
+    }
+
+    #[test]
+    fn test_warn_trailing_ellipsis() {
+        let config = parse_args(args(&["rcop", "--warn-trailing-ellipsis"]))
+            .unwrap()
+            .config;
+        let report = analyze("feat(ui): add thing...", &config);
+        assert!(report.valid());
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(rules, vec!["description-ellipsis"]);
+        assert!(analyze("feat(ui): add thing", &config)
+            .diagnostics
+            .is_empty());
+        let rules: Vec<&str> = analyze("feat(ui): add thing...", &Config::default())
+            .diagnostics
+            .iter()
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(rules, vec!["trailing-period"]);
+    }
+
+    #[test]
+    fn test_warn_trailing_issue_ref() {
+        let config = parse_args(args(&["rcop", "--warn-trailing-issue-ref"]))
+            .unwrap()
+            .config;
+        let report = analyze("docs: resolve bug #123", &config);
+        assert!(report.valid());
+        assert_eq!(
+            report.diagnostics[0].message,
+            "Description ends with the issue reference '#123', consider moving it into a footer like 'Closes: #123'"
+        );
+        assert!(analyze("docs: resolve #123 in the parser", &config)
+            .diagnostics
+            .is_empty());
+        assert!(analyze("docs: resolve bug #123", &Config::default())
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_warn_url_only_description() {
+        let config = parse_args(args(&["rcop", "--warn-url-only-description"]))
+            .unwrap()
+            .config;
+        let report = analyze("docs: https://example.com/issues/1", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "url-only-description");
+        assert!(
+            analyze("docs: https://example.com/issues/1", &Config::default())
+                .diagnostics
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_require_revert_sha() {
+        let config = parse_args(args(&["rcop", "--require-revert-sha"]))
+            .unwrap()
+            .config;
+        let message = "revert: add the login cache\n\nThis reverts commit 1f0e3f4b8a2c9d7e6b5a4c3d2e1f0a9b8c7d6e5f.";
+        assert!(analyze(message, &config).diagnostics.is_empty());
+        let report = analyze("revert: add the login cache", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "revert-sha");
+        assert!(analyze("revert: add the login cache", &Config::default())
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_require_release_version() {
+        let config = parse_args(args(&["rcop", "--require-release-version"]))
+            .unwrap()
+            .config;
+        assert!(analyze("chore(release): v1.2.3", &config).valid());
+        assert_eq!(
+            analyze("chore(release): bump", &config).errors()[0].code,
+            "release-version"
+        );
+        assert!(analyze("chore(deps): bump", &config).valid());
+        assert!(analyze("chore(release): bump", &Config::default()).valid());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--require-release-version",
+            "--release-commit",
+            "build(publish)",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze("chore(release): bump", &config).valid());
+        assert!(!analyze("build(publish): bump", &config).valid());
+        assert!(parse_args(args(&["rcop", "--release-commit", "(release)"])).is_err());
+    }
+
+    #[test]
+    fn test_body_mentions_scope() {
+        let config = parse_args(args(&["rcop", "--body-mentions-scope"]))
+            .unwrap()
+            .config;
+        assert!(
+            analyze("fix(auth): x\n\nThe auth token expired too early.", &config)
+                .diagnostics
+                .is_empty()
+        );
+        let report = analyze("fix(auth): x\n\nThe token expired too early.", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "body-mentions-scope");
+        assert!(analyze("docs: x\n\nNo scope.", &config)
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_type() {
+        let config = parse_args(args(&["rcop", "--fuzzy-type"])).unwrap().config;
+        let report = analyze("fet(x): y", &config);
+        assert!(report.valid());
+        assert_eq!(report.commit.commit_type, "feat");
+        assert_eq!(report.diagnostics[0].code, "type-fuzzy");
+        assert_eq!(
+            report.diagnostics[0].message,
+            "Commit type 'fet' is not known, accepted as 'feat'"
+        );
+        assert_eq!(analyze("xyz(x): y", &config).errors()[0].code, "type-enum");
+        assert!(!analyze("fet(x): y", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_warn_non_imperative() {
+        let config = parse_args(args(&["rcop", "--warn-non-imperative"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze("docs: updated the readme", &config).diagnostics[0].code,
+            "imperative-mood"
+        );
+        assert!(analyze("docs: address the review", &config)
+            .diagnostics
+            .is_empty());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--warn-non-imperative",
+            "--non-imperative-suffixes",
+            "ed,te",
+            "--imperative-exceptions",
+            "create",
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(config.non_imperative_suffixes, vec!["ed", "te"]);
+        assert!(!analyze("docs: update the readme", &config)
+            .diagnostics
+            .is_empty());
+        assert!(analyze("docs: create the readme", &config)
+            .diagnostics
+            .is_empty());
+        assert!(analyze("docs: adds the readme", &config)
+            .diagnostics
+            .is_empty());
+        assert!(analyze("docs: updated the readme", &Config::default())
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_warn_mixed_scope_separators() {
+        let config = parse_args(args(&["rcop", "--warn-mixed-scope-separators"]))
+            .unwrap()
+            .config;
+        let report = analyze("fix(api/users.auth): expire tokens", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "scope-separators");
+        assert!(analyze("fix(api/users/auth): expire tokens", &config)
+            .diagnostics
+            .is_empty());
+        assert!(
+            analyze("fix(api/users.auth): expire tokens", &Config::default())
+                .diagnostics
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_warn_redundant_type() {
+        let config = parse_args(args(&["rcop", "--warn-redundant-type"]))
+            .unwrap()
+            .config;
+        let report = analyze("fix(auth): fix login", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "redundant-type");
+        assert!(analyze("fix(auth): resolve login", &config)
+            .diagnostics
+            .is_empty());
+        assert!(analyze("fix(auth): fix login", &Config::default())
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ascii_only_subject() {
+        let config = parse_args(args(&["rcop", "--ascii-only-subject"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze("docs: explain the résumé export", &config).errors()[0].code,
+            "subject-ascii"
+        );
+        assert!(analyze("docs: explain the resume export", &config).valid());
+        assert!(analyze("docs: explain the résumé export", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_encoding_check() {
+        let config = parse_args(args(&["rcop", "--encoding-check"]))
+            .unwrap()
+            .config;
+        let report = analyze("docs: explain the rÃ©sumÃ© export", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "description-encoding");
+        assert!(analyze("docs: explain the résumé export", &config)
+            .diagnostics
+            .is_empty());
+        assert!(
+            analyze("docs: explain the rÃ©sumÃ© export", &Config::default())
+                .diagnostics
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_allowed_verbs() {
+        let config = parse_args(args(&["rcop", "--allowed-verbs", "add,fix,remove,update"]))
+            .unwrap()
+            .config;
+        assert!(analyze("docs: update the readme", &config).valid());
+        assert_eq!(
+            analyze("docs: rewrite the readme", &config).errors()[0].code,
+            "description-verb"
+        );
+        assert!(analyze("docs: rewrite the readme", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_require_body_sections() {
+        let config = parse_args(args(&["rcop", "--require-body-sections", "Why,What"]))
+            .unwrap()
+            .config;
+        assert!(analyze(
+            "fix(api): x\n\n## Why\nIt timed out.\n\n## What\nA longer timeout.",
+            &config
+        )
+        .valid());
+        let rules = |message| {
+            analyze(message, &config)
+                .errors()
+                .iter()
+                .map(|error| error.code)
+                .collect::<Vec<&str>>()
+        };
+        assert_eq!(
+            rules("fix(api): x\n\nWhy: it timed out."),
+            vec!["body-sections"]
+        );
+        assert_eq!(rules("fix(api): x"), vec!["body-sections", "body-sections"]);
+        assert!(analyze("fix(api): x", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_forbidden_body_words() {
+        let config = parse_args(args(&[
+            "rcop",
+            "--forbidden-body-words",
+            "console.log,debugger,DO NOT MERGE",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze("docs: typo\n\nFixes the spelling.", &config).valid());
+        let report = analyze("docs: typo\n\nFixes the spelling.\nDO NOT MERGE", &config);
+        assert_eq!(report.errors()[0].code, "body-forbidden-words");
+        assert!(analyze("docs: DO NOT MERGE", &config).valid());
+    }
+
+    #[test]
+    fn test_allowed_footers() {
+        let config = parse_args(args(&[
+            "rcop",
+            "--allowed-footers",
+            "Refs,Closes,Co-authored-by,Signed-off-by,BREAKING CHANGE",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze("docs: typo\n\nCloses: #12", &config).valid());
+        let report = analyze("docs: typo\n\nCloses: #12\nRandom-Token: x", &config);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].code, "footer-allowed");
+    }
+
+    #[test]
+    fn test_issue_footer_style() {
+        let config = parse_args(args(&["rcop", "--issue-footer-style", "colon-hash"]))
+            .unwrap()
+            .config;
+        assert!(analyze("docs: x\n\nCloses: #12", &config).valid());
+        assert_eq!(
+            analyze("docs: x\n\nCloses #12", &config).errors()[0].code,
+            "issue-footer-style"
+        );
+        assert!(analyze("docs: x\n\nCloses #12", &Config::default()).valid());
+        assert!(parse_args(args(&["rcop", "--issue-footer-style", "gitlab"])).is_err());
+    }
+
+    #[test]
+    fn test_require_footer_blank_line() {
+        let config = parse_args(args(&["rcop", "--require-footer-blank-line"]))
+            .unwrap()
+            .config;
+        let report = analyze("docs: x\n\nSome details.\nCloses: #12", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "footer-blank-line");
+        assert!(analyze("docs: x\n\nSome details.\n\nCloses: #12", &config)
+            .diagnostics
+            .is_empty());
+    }
+
+    #[test]
+    fn test_co_author_email() {
+        let config = Config::default();
+        assert!(analyze(
+            "docs: typo\n\nCo-authored-by: Jane Doe <jane@example.com>",
+            &config
+        )
+        .diagnostics
+        .is_empty());
+        let report = analyze("docs: typo\n\nCo-authored-by: Jane Doe", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "co-author-email");
+    }
+
+    #[test]
+    fn test_require_capitalized_footer_tokens() {
+        let input = "fix(api): handle timeouts\n\nsigned-off-by: Jane Doe <jane@example.com>";
+        assert!(analyze(input, &Config::default()).diagnostics.is_empty());
+
+        let config = parse_args(args(&["rcop", "--require-capitalized-footer-tokens"]))
+            .unwrap()
+            .config;
+        let rules: Vec<&str> = analyze(input, &config)
+            .diagnostics
+            .iter()
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(rules, vec!["footer-token-case"]);
+
+        let input = "fix(api): handle timeouts\n\nSigned-off-by: Jane Doe <jane@example.com>";
+        assert!(analyze(input, &config).diagnostics.is_empty());
+
+        let input = "fix(api): handle timeouts\n\n2fa-reviewed-by: Jane";
+        assert_eq!(analyze(input, &config).commit.footers.len(), 1);
+        assert!(analyze(input, &config).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_types_stdin() {
+        let path = env::temp_dir().join("rcop-test-types-stdin");
+        std::fs::write(&path, "wild(scope): a custom type").unwrap();
+
+        let mut parsed = parse_args(args(&[
+            "rcop",
+            "--types-stdin",
+            "--file",
+            path.to_str().unwrap(),
+        ]))
+        .unwrap();
+        let input = open_input(
+            Cursor::new(b"wild=scope,description\n"),
+            &mut parsed,
+            git::run,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.config.commit_types,
+            parse_commit_types("wild=scope,description".to_string())
+        );
+        let mut output = Vec::new();
+        assert!(run(input, &parsed, &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().contains("a custom type"));
+
+        let mut parsed = parse_args(args(&["rcop", "--types-stdin"])).unwrap();
+        let result = open_input(Cursor::new(b"wild="), &mut parsed, git::run)
+            .err()
+            .unwrap();
+        assert_eq!(result.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_preprocess() {
+        let parsed = parse_args(args(&["rcop", "--preprocess", "sed 's/^TEAM-1 //'"])).unwrap();
+        assert_eq!(parsed.preprocess.as_deref(), Some("sed 's/^TEAM-1 //'"));
+        let input = preprocess(Cursor::new("TEAM-1 fix(ui): x"), |text| {
+            Ok(text.trim_start_matches("TEAM-1 ").to_string())
+        })
+        .unwrap();
+        let mut output = Vec::new();
+        assert!(run(input, &parsed, &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().contains("| fix "));
+
+        let result = preprocess(Cursor::new("fix(ui): x"), |_| Err(Error::other("failed")));
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell() {
+        assert_eq!(
+            shell("sed 's/^TEAM-1 //'", "TEAM-1 fix(ui): x\n").unwrap(),
+            "fix(ui): x\n"
+        );
+        let result = shell("cat > /dev/null; echo nope >&2; exit 3", "fix(ui): x").unwrap_err();
+        assert!(result.to_string().contains("nope"), "{}", result);
+    }
+
+    #[test]
+    fn test_file_and_stdin() {
+        let path = env::temp_dir().join("rcop-test-file-and-stdin");
+        std::fs::write(&path, "docs: from the file").unwrap();
+
+        let mut parsed = parse_args(args(&["rcop", "--file", path.to_str().unwrap()])).unwrap();
+        assert!(input_warning(&parsed, true)
+            .unwrap()
+            .contains("read from --file"));
+        let input = open_input(Cursor::new(b"wip: from stdin"), &mut parsed, git::run).unwrap();
+        let mut output = Vec::new();
+        assert!(run(input, &parsed, &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().contains("from the file"));
+
+        assert_eq!(input_warning(&parsed, false), None);
+        assert_eq!(
+            input_warning(&parse_args(args(&["rcop"])).unwrap(), true),
+            None
+        );
+        parsed.types_stdin = true;
+        assert_eq!(input_warning(&parsed, true), None);
+    }
+
+    #[test]
+    fn test_rule_codes() {
+        let cases: &[(&[&str], &str, &str)] = &[
+            (&[], "add login", "header-format"),
+            (&[], "feat(api): add <thing>", "header-placeholder"),
+            (&[], "wip: add login", "type-enum"),
+            (&[], "feat: add login", "scope-required"),
+            (&[], "docs: x\n\n  \n", "body-empty"),
+            (
+                &[],
+                "docs(api): add login\n\nadd login",
+                "body-duplicates-subject",
+            ),
+            (&[], "feat(): add login", "scope-empty"),
+            (&[], "feat(api,api): add login", "scope-duplicate"),
+            (&[], "docs: x\n\nBREAKING CHANGE:", "breaking-change-empty"),
+            (&[], "docs: add login.", "trailing-period"),
+            (&[], "docs: x\n\nCo-authored-by: Jane", "co-author-email"),
+            (&["--types", "docs=scope:ui"], "docs(api): x", "scope-enum"),
+            (
+                &["--no-scope-for", "docs"],
+                "docs(api): x",
+                "scope-forbidden",
+            ),
+            (&["--deny-scopes", "misc"], "docs(misc): x", "scope-denied"),
+            (&["--sort-scopes"], "docs(ui,api): x", "scope-order"),
+            (
+                &["--breaking-allowed-for", "feat"],
+                "docs!: x",
+                "breaking-not-allowed",
+            ),
+            (&["--scope-case", "kebab"], "docs(Api): x", "scope-case"),
+            (
+                &["--subject-case", "sentence"],
+                "docs: add login",
+                "subject-case",
+            ),
+            (
+                &["--max-message-bytes", "4"],
+                "docs: x",
+                "max-message-bytes",
+            ),
+            (
+                &["--max-description-length", "3"],
+                "docs: add login",
+                "max-description-length",
+            ),
+            (
+                &["--max-body-lines", "1"],
+                "docs: x\n\na\nb",
+                "max-body-lines",
+            ),
+            (
+                &["--max-consecutive-blank-lines", "0"],
+                "docs: x\n\na\n\nb",
+                "body-blank-lines",
+            ),
+            (&["--prefix-pattern", "fix"], "docs: x", "prefix-pattern"),
+            (
+                &["--description-pattern", "x"],
+                "docs: add login",
+                "description-pattern",
+            ),
+            (
+                &["--strict-separator-spacing"],
+                "docs:  x",
+                "separator-spacing",
+            ),
+            (
+                &["--warn-trailing-ellipsis"],
+                "docs: add login...",
+                "description-ellipsis",
+            ),
+            (
+                &["--warn-trailing-issue-ref"],
+                "docs: add login #12",
+                "trailing-issue-ref",
+            ),
+            (
+                &["--warn-url-only-description"],
+                "docs: https://example.com",
+                "url-only-description",
+            ),
+            (
+                &["--warn-non-imperative"],
+                "docs: added login",
+                "imperative-mood",
+            ),
+            (&["--fuzzy-type"], "doc: add login", "type-fuzzy"),
+            (
+                &["--warn-redundant-type"],
+                "docs: docs for login",
+                "redundant-type",
+            ),
+            (&["--require-revert-sha"], "revert: add login", "revert-sha"),
+            (
+                &["--require-release-version"],
+                "chore(release): bump",
+                "release-version",
+            ),
+            (
+                &["--body-mentions-scope"],
+                "docs(api): x\n\nmore",
+                "body-mentions-scope",
+            ),
+            (&["--ascii-only-subject"], "docs: add café", "subject-ascii"),
+            (
+                &["--encoding-check"],
+                "docs: add cafÃ©",
+                "description-encoding",
+            ),
+            (
+                &["--allowed-verbs", "add"],
+                "docs: fix login",
+                "description-verb",
+            ),
+            (
+                &["--require-body-sections", "Why"],
+                "docs: x\n\nmore",
+                "body-sections",
+            ),
+            (
+                &["--forbidden-body-words", "wip"],
+                "docs: x\n\nstill wip",
+                "body-forbidden-words",
+            ),
+            (
+                &["--require-footer-blank-line"],
+                "docs: x\n\nmore\nRefs: #1",
+                "footer-blank-line",
+            ),
+            (
+                &["--allowed-footers", "Refs"],
+                "docs: x\n\nCloses: #1",
+                "footer-allowed",
+            ),
+            (
+                &["--issue-footer-style", "hash"],
+                "docs: x\n\nCloses: #1",
+                "issue-footer-style",
+            ),
+            (
+                &["--require-capitalized-footer-tokens"],
+                "docs: x\n\nrefs: #1",
+                "footer-token-case",
+            ),
+            (
+                &["--warn-mixed-scope-separators"],
+                "docs(api/users.auth): x",
+                "scope-separators",
+            ),
+            (
+                &["--squash-aware"],
+                "Add login (#1)\n\ndocs: x",
+                "squash-header",
+            ),
+        ];
+        for (options, message, code) in cases {
+            let config = parse_args(args(&[&["rcop"], *options].concat()))
+                .unwrap()
+                .config;
+            let rules: Vec<&str> = analyze(message, &config)
+                .diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect();
+            assert!(
+                rules.contains(code),
+                "Expected '{}' for '{}' with {:?}, got {:?}",
+                code,
+                message,
+                options,
+                rules
+            );
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_spans() {
+        let config = parse_args(args(&["rcop", "--strict-separator-spacing"]))
+            .unwrap()
+            .config;
+        let message = "feat():  x";
+        let diagnostics = analyze(message, &config).diagnostics;
+        let span = |rule: &str| {
+            diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.code == rule)
+                .and_then(|diagnostic| diagnostic.span)
+        };
+        assert_eq!(span("scope-empty"), Some((4, 6)));
+        assert_eq!(span("separator-spacing"), Some((7, 9)));
+        assert_eq!(span("scope-required"), None);
+
+        let message = "feat(api): add <thing>";
+        let (start, end) = analyze(message, &Config::default()).diagnostics[0]
+            .span
+            .unwrap();
+        assert_eq!(&message[start..end], "<thing>");
+        assert_eq!(
+            analyze("add login", &Config::default()).diagnostics[0].span,
+            Some((0, 9))
+        );
+
+        let config = parse_args(args(&["rcop", "--squash-aware"]))
+            .unwrap()
+            .config;
+        let message = "Add login (#1)\n\nfeat(): add <thing>";
+        let (start, end) = analyze(message, &config)
+            .diagnostics
+            .iter()
+            .find_map(|diagnostic| diagnostic.span)
+            .unwrap();
+        assert_eq!(&message[start..end], "()");
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--scope-case",
+            "kebab",
+            "--subject-case",
+            "lower",
+        ]))
+        .unwrap()
+        .config;
+        let message = "wip(Api): Add login.";
+        let diagnostics = analyze(message, &config).diagnostics;
+        let text = |rule: &str| {
+            diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.code == rule)
+                .and_then(|diagnostic| diagnostic.span)
+                .map(|(start, end)| &message[start..end])
+        };
+        assert_eq!(text("type-enum"), Some("wip"));
+        assert_eq!(text("scope-case"), Some("Api"));
+        assert_eq!(text("subject-case"), Some("Add login."));
+        assert_eq!(text("trailing-period"), Some("."));
+    }
+
+    #[test]
+    fn test_checked_rules() {
+        let config = parse_args(args(&["rcop", "--disable-rules", "trailing-period"]))
+            .unwrap()
+            .config;
+        let report = analyze("feat: add login.", &config);
+        let outcomes: Vec<(&str, bool)> = report
+            .outcomes()
+            .into_iter()
+            .map(|(rule, diagnostic)| (rule, diagnostic.is_none()))
+            .collect();
+        assert!(outcomes.contains(&("header-format", true)));
+        assert!(outcomes.contains(&("type-enum", true)));
+        assert!(outcomes.contains(&("scope-required", false)));
+        assert!(!outcomes.iter().any(|(rule, _)| *rule == "trailing-period"));
+        assert!(!outcomes.iter().any(|(rule, _)| *rule == "max-body-lines"));
+
+        let report = analyze("add login", &config);
+        assert_eq!(report.checked, vec!["header-format", "header-placeholder"]);
+    }
+
+    #[test]
+    fn test_prefix_pattern() {
+        let config = parse_args(args(&[
+            "rcop",
+            "--prefix-pattern",
+            r"(feat|fix)\((api|web)\)|release\(v\d+\)",
+        ]))
+        .unwrap()
+        .config;
+        assert!(analyze("fix(web): add login", &config).valid());
+        assert!(analyze("release(v2): cut the release", &config).valid());
+        let report = analyze("feat(db): add login", &config);
+        let rules: Vec<&str> = report.errors().iter().map(|error| error.code).collect();
+        assert_eq!(rules, vec!["prefix-pattern"]);
+        assert!(!analyze("feat: add login", &config).valid());
+        assert_eq!(
+            analyze("feat(api): add login.", &config).diagnostics[0].code,
+            "trailing-period"
+        );
+        assert!(!analyze("release(v2): cut the release", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_placeholder_patterns() {
+        let config = Config::default();
+        let report = analyze("feat(<scope>): <description>", &config);
+        assert!(!report.valid());
+        assert_eq!(report.errors()[0].code, "header-placeholder");
+        assert_eq!(
+            analyze("fix(api): XXX", &config).errors()[0].code,
+            "header-placeholder"
+        );
+        assert!(analyze("fix(api): accept Vec<u8> payloads", &config).valid());
+
+        let config = parse_args(args(&["rcop", "--placeholder-patterns", "TODO:?,FIXME"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze("fix(api): TODO describe", &config).errors()[0].code,
+            "header-placeholder"
+        );
+        assert!(analyze("fix(api): XXX", &config).valid());
+
+        let config = parse_args(args(&["rcop", "--placeholder-patterns", ""]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze("feat(<scope>): <description>", &config).errors()[0].code,
+            "header-format"
+        );
+        assert!(parse_args(args(&["rcop", "--placeholder-patterns", "("])).is_err());
+    }
+
+    #[test]
+    fn test_description_pattern() {
+        let config = parse_args(args(&["rcop", "--description-pattern", r".*\(#\d+\)"]))
+            .unwrap()
+            .config;
+        assert!(analyze("docs: update readme (#12)", &config).valid());
+
+        let report = analyze("docs: update readme", &config);
+        assert!(!report.valid());
+        assert_eq!(report.errors()[0].code, "description-pattern");
+
+        let result = parse_args(args(&["rcop", "--description-pattern", "("]))
+            .unwrap_err()
+            .kind();
+        assert_eq!(result, ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_suggest_swapped_type_and_scope() {
+        let report = analyze("(api)feat: x", &Config::default());
+        assert_eq!(report.errors()[0].code, "header-format");
+        assert!(report.errors()[0]
+            .message
+            .ends_with(", did you mean 'feat(api): x'?"));
+
+        let report = analyze("api(feat): x", &Config::default());
+        assert_eq!(
+            report.errors()[0].message,
+            "Commit type not allowed, did you mean 'feat(api): x'?"
+        );
+    }
+
+    #[test]
+    fn test_ignore_file() {
+        let path = env::temp_dir().join("rcop-test-ignore-file");
+        fs::write(
+            &path,
+            "# historical commits\nInitial commit\nMerge branch *\n",
+        )
+        .unwrap();
+        let mut parsed = parse_args(args(&[
+            "rcop",
+            "--batch",
+            "--ignore-file",
+            path.to_str().unwrap(),
+        ]))
+        .unwrap();
+        load_ignore_file(&mut parsed).unwrap();
+
+        let input = "Initial commit\0Merge branch 'main' into dev\0docs: typo\0Update stuff";
+        let statuses: Vec<String> = lint_all(&split_batch(input), &parsed)
+            .unwrap()
+            .iter()
+            .map(AnalysisResult::status)
+            .collect();
+        assert_eq!(statuses, vec!["ignored", "ignored", "true", "false"]);
+    }
+
+    #[test]
+    fn test_allow_at_in_scope() {
+        let config = parse_args(args(&["rcop", "--allow-at-in-scope"]))
+            .unwrap()
+            .config;
+        let report = analyze("feat(pkg@1.2.3): x", &config);
+        assert!(report.valid());
+        assert_eq!(report.commit.scope, "pkg@1.2.3");
+        assert_eq!(
+            analyze("feat(pkg@1.2.3): x", &Config::default()).errors()[0].code,
+            "header-format"
+        );
+    }
+
+    #[test]
+    fn test_scopes_file() {
+        let path = env::temp_dir().join("rcop-test-scopes-file");
+        fs::write(&path, "# crates\napi\ncli\n\nui\n").unwrap();
+        let config = parse_args(args(&["rcop", "--scopes-file", path.to_str().unwrap()]))
+            .unwrap()
+            .config;
+        assert_eq!(config.allowed_scopes, vec!["api", "cli", "ui"]);
+        assert!(analyze("feat(api): x", &config).valid());
+        assert!(analyze("docs: x", &config).valid());
+        assert_eq!(
+            analyze("feat(core): x", &config).errors()[0].message,
+            "Scope 'core' is not in the list of allowed scopes"
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            parse_args(args(&["rcop", "--scopes-file", "/does/not/exist"]))
+                .err()
+                .unwrap()
+                .kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_scopes_from_codeowners() {
+        let path = env::temp_dir().join("rcop-test-codeowners");
+        fs::write(
+            &path,
+            "* @org/maintainers\n/src/api/ @org/backend\n/web/** @org/frontend\n",
+        )
+        .unwrap();
+        let config = parse_args(args(&[
+            "rcop",
+            "--scopes-from-codeowners",
+            path.to_str().unwrap(),
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(config.allowed_scopes, vec!["api", "web"]);
+        assert!(analyze("feat(web): x", &config).valid());
+        assert_eq!(
+            analyze("feat(core): x", &config).errors()[0].code,
+            "scope-enum"
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reviewers_file() {
+        let path = env::temp_dir().join("rcop-test-reviewers-file");
+        fs::write(&path, "# maintainers\njane@example.com\nJohn Roe\n").unwrap();
+        let config = parse_args(args(&["rcop", "--reviewers-file", path.to_str().unwrap()]))
+            .unwrap()
+            .config;
+        fs::remove_file(&path).unwrap();
+        assert!(analyze(
+            "fix(api): x\n\nA fix.\n\nReviewed-by: Jane Doe <jane@example.com>",
+            &config
+        )
+        .valid());
+        assert_eq!(
+            analyze(
+                "fix(api): x\n\nA fix.\n\nReviewed-by: Eve <eve@example.com>",
+                &config
+            )
+            .errors()[0]
+                .code,
+            "footer-reviewer"
+        );
+        assert!(analyze(
+            "fix(api): x\n\nA fix.\n\nReviewed-by: Eve <eve@example.com>",
+            &Config::default()
+        )
+        .valid());
+    }
+
+    #[test]
+    fn test_deny_scopes() {
+        let config = parse_args(args(&["rcop", "--deny-scopes", "misc,stuff,tmp"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            analyze("feat(misc): x", &config).errors()[0].code,
+            "scope-denied"
+        );
+        assert!(analyze("feat(api): x", &config).valid());
+        assert!(analyze("feat(misc): x", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_sort_scopes() {
+        let config = parse_args(args(&["rcop", "--sort-scopes"])).unwrap().config;
+        assert!(analyze("feat(api,core): add endpoint", &config)
+            .diagnostics
+            .is_empty());
+        let report = analyze("feat(core,api): add endpoint", &config);
+        assert!(report.valid());
+        assert_eq!(report.diagnostics[0].code, "scope-order");
+        assert!(analyze("feat(core,api): add endpoint", &Config::default())
+            .diagnostics
+            .is_empty());
+        let report = analyze("feat(api,api): add endpoint", &Config::default());
+        assert_eq!(report.diagnostics[0].code, "scope-duplicate");
+    }
+
+    #[test]
+    fn test_empty_scope_parens() {
+        let rules = |config: &Config| {
+            analyze("docs(): x", config)
+                .diagnostics
+                .iter()
+                .map(|d| (d.code, d.severity))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            rules(&Config::default()),
+            vec![("scope-empty", Severity::Error)]
+        );
+        let config = parse_args(args(&["rcop", "--warn-empty-scope-parens"]))
+            .unwrap()
+            .config;
+        assert_eq!(rules(&config), vec![("scope-empty", Severity::Warning)]);
+        assert_eq!(analyze("docs(): x", &config).commit.scope, "");
+        let config = parse_args(args(&["rcop", "--empty-scope-parens", "accept"]))
+            .unwrap()
+            .config;
+        assert!(rules(&config).is_empty());
+        let config = parse_args(args(&["rcop", "--empty-scope-parens", "error"]))
+            .unwrap()
+            .config;
+        assert!(!analyze("docs(): x", &config).valid());
+        assert!(parse_args(args(&["rcop", "--empty-scope-parens", "ignore"])).is_err());
+    }
+
+    #[test]
+    fn test_require_lowercase_scope_for() {
+        let config = parse_args(args(&["rcop", "--require-lowercase-scope-for", "feat,fix"]))
+            .unwrap()
+            .config;
+        let report = analyze("feat(API): x", &config);
+        assert_eq!(report.errors()[0].code, "scope-case");
+        assert_eq!(
+            report.errors()[0].message,
+            "Scope 'API' has to be lowercase"
+        );
+        assert!(analyze("feat(api): x", &config).valid());
+        assert!(analyze("docs(API): x", &config).valid());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--require-lowercase-scope-for",
+            "feat",
+            "--scope-case",
+            "kebab",
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(analyze("feat(API): x", &config).errors().len(), 1);
+        assert!(!analyze("docs(API): x", &config).valid());
+    }
+
+    #[test]
+    fn test_scope_case() {
+        let config = parse_args(args(&["rcop", "--scope-case", "kebab"]))
+            .unwrap()
+            .config;
+        let report = analyze("feat(UserProfile): x", &config);
+        assert_eq!(report.errors()[0].code, "scope-case");
+        assert!(analyze("feat(user-profile): x", &config).valid());
+        assert!(analyze("feat(UserProfile): x", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_commitlint_config() {
+        let path = env::temp_dir().join("rcop-test-commitlint-config.json");
+        fs::write(
+            &path,
+            r#"{"rules": {"type-enum": [2, "always", ["feat", "fix"]], "subject-max-length": [2, "always", 10]}}"#,
+        )
+        .unwrap();
+        let config = parse_args(args(&[
+            "rcop",
+            "--max-description-length",
+            "20",
+            "--commitlint-config",
+            path.to_str().unwrap(),
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(config.max_description_length, Some(20));
+        assert!(analyze("fix: a short fix", &config).valid());
+        assert!(!analyze("docs: a short fix", &config).valid());
+        assert_eq!(
+            analyze("fix: a rather long description", &config).errors()[0].code,
+            "max-description-length"
+        );
+    }
+
+    #[test]
+    fn test_require_type_in() {
+        assert!(analyze("style: format", &Config::default()).valid());
+        let config = parse_args(args(&[
+            "rcop",
+            "--require-type-in",
+            "feat,fix,docs,chore,wip",
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(
+            analyze("style: format", &config).errors()[0].code,
+            "type-enum"
+        );
+        assert!(analyze("chore: format", &config).valid());
+        let names: Vec<&str> = config
+            .commit_types
+            .iter()
+            .map(|commit_type| commit_type.commit_type.as_str())
+            .collect();
+        assert_eq!(names, vec!["feat", "fix", "chore", "docs"]);
+    }
+
+    #[test]
+    fn test_breaking_allowed_for() {
+        let config = parse_args(args(&["rcop", "--breaking-allowed-for", "feat,fix"]))
+            .unwrap()
+            .config;
+        assert!(analyze("feat(api)!: drop v1", &config).valid());
+        assert_eq!(
+            analyze("docs!: x", &config).errors()[0].code,
+            "breaking-not-allowed"
+        );
+        assert_eq!(
+            analyze("docs: x\n\nBREAKING CHANGE: moved", &config).errors()[0].code,
+            "breaking-not-allowed"
+        );
+        assert!(analyze("docs: x", &config).valid());
+        assert!(analyze("docs!: x", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_baseline() {
+        let path = env::temp_dir().join("rcop-test-baseline");
+        let path = path.to_str().unwrap();
+        let old = "feat: add login\0docs: add a guide.\0";
+        let mut parsed = parse_args(args(&["rcop", "--batch", "--write-baseline", path])).unwrap();
+        let mut output = Vec::new();
+        assert!(run(Cursor::new(old), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("rcop: wrote 2 violations to the baseline {}\n", path)
+        );
+        assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 3);
+
+        parsed = parse_args(args(&["rcop", "--batch", "--baseline", path])).unwrap();
+        assert_eq!(parsed.baseline.len(), 2);
+        let mut output = Vec::new();
+        assert!(run(Cursor::new(old), &parsed, &mut output).unwrap());
+
+        let reports = lint_all(&["feat: add login", "fix: add login"], &parsed).unwrap();
+        assert!(reports[0].diagnostics.is_empty());
+        assert_eq!(reports[1].errors()[0].code, "scope-required");
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            parse_args(args(&["rcop", "--baseline", "/does/not/exist"]))
+                .err()
+                .unwrap()
+                .kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_batch_only_types() {
+        let input = "feat(api): add endpoint\0style: reformat\0fix: missing scope\0";
+        let parsed = parse_args(args(&["rcop", "--batch", "--only-types", "feat,fix"])).unwrap();
+
+        let reports: Vec<AnalysisResult> = split_batch(input)
+            .iter()
+            .map(|message| analyze(message, &parsed.config))
+            .collect();
+        let statuses: Vec<String> = reports.iter().map(AnalysisResult::status).collect();
+        assert_eq!(statuses, vec!["true", "skipped", "false"]);
+
+        let mut output = Vec::new();
+        let valid = run(
+            Cursor::new("feat(api): add endpoint\0style: reformat\0"),
+            &parsed,
+            &mut output,
+        )
+        .unwrap();
+        assert!(valid);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("skipped"));
+        assert!(output.contains("add endpoint"));
+    }
+
+    #[test]
+    fn test_no_scope_for() {
+        let config = parse_args(args(&["rcop", "--no-scope-for", "chore,ci"]))
+            .unwrap()
+            .config;
+        let report = analyze("chore(x): y", &config);
+        assert_eq!(report.errors()[0].code, "scope-forbidden");
+        assert_eq!(
+            report.errors()[0].message,
+            "Commit type doesn't allow a scope, but got 'x'"
+        );
+        assert!(analyze("chore: y", &config).valid());
+        assert!(analyze("ci: y", &config).valid());
+        assert!(analyze("chore(x): y", &Config::default()).valid());
+    }
+
+    #[test]
+    fn test_require_description_for() {
+        let types = "wip=;chore=scope";
+        let config = parse_args(args(&["rcop", "--types", types]))
+            .unwrap()
+            .config;
+        assert!(analyze("wip:", &config).valid());
+
+        let config = parse_args(args(&[
+            "rcop",
+            "--require-description-for",
+            "wip,chore",
+            "--types",
+            types,
+        ]))
+        .unwrap()
+        .config;
+        assert_eq!(
+            analyze("wip:", &config).errors()[0].code,
+            "description-required"
+        );
+        assert!(analyze("wip: message", &config).valid());
+        assert_eq!(
+            config.commit_types[1].required,
+            vec!["scope".to_string(), "description".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_scope_types() {
+        let config = parse_args(args(&["rcop", "--scope-types", "docs=docs;ci=ci,build"]))
+            .unwrap()
+            .config;
+        assert_eq!(
+            config.scope_types,
+            vec![
+                ("docs".to_string(), vec!["docs".to_string()]),
+                (
+                    "ci".to_string(),
+                    vec!["ci".to_string(), "build".to_string()]
+                ),
+            ]
+        );
+        let report = analyze("feat(docs/readme): x", &config);
+        assert_eq!(report.errors()[0].code, "scope-type");
+        assert_eq!(
+            report.errors()[0].message,
+            "Scope 'docs/readme' only allows the types docs, but got 'feat'"
+        );
+        assert!(analyze("docs(docs/readme): x", &config).valid());
+        assert!(analyze("build(ci): x", &config).valid());
+        assert!(analyze("feat(api): x", &config).valid());
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        for format in ["table", "github", "json", "markdown", "toml"] {
+            let parsed = parse_args(args(&["rcop", "--batch", "-f", format])).unwrap();
+            let mut output = Vec::new();
+            assert!(
+                run(Cursor::new(b""), &parsed, &mut output).unwrap(),
+                "{}",
+                format
+            );
+        }
+        let parsed = parse_args(args(&["rcop", "--batch", "-f", "json"])).unwrap();
+        let mut output = Vec::new();
+        run(Cursor::new(b""), &parsed, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_since_without_commits() {
+        let mut parsed = parse_args(args(&["rcop", "--since", "7d"])).unwrap();
+        let input = open_input(Cursor::new(b""), &mut parsed, |_| Ok("".to_string())).unwrap();
+        let mut output = Vec::new();
+        assert!(run(input, &parsed, &mut output).unwrap());
+    }
+
+    #[test]
+    fn test_since() {
+        let mut parsed = parse_args(args(&["rcop", "--since", "7d"])).unwrap();
+        assert!(parsed.batch);
+        let input = open_input(Cursor::new(b""), &mut parsed, |git_args| {
+            assert_eq!(git_args, git::log_args("7 days ago"));
+            Ok("feat(api): add endpoint\n\0docs: typo\n\0wrong\n\0".to_string())
+        })
+        .unwrap();
+        let mut output = Vec::new();
+        assert!(!run(input, &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("add endpoint"));
+        assert!(output.contains("typo"));
+        assert!(output.contains("Error! (#3)"));
+
+        let mut parsed = parse_args(args(&["rcop", "--since", "7d", "--file", "x"])).unwrap();
+        assert!(open_input(Cursor::new(b""), &mut parsed, git::run).is_err());
+        assert!(parse_args(args(&["rcop", "--since", "7y"])).is_err());
+    }
+
+    #[test]
+    fn test_fail_fast() {
+        let messages = vec!["feat(api): add endpoint", "nope: x", "docs: typo", "wrong"];
+
+        let parsed = parse_args(args(&["rcop", "--batch"])).unwrap();
+        assert_eq!(lint_all(&messages, &parsed).unwrap().len(), 4);
+
+        let parsed = parse_args(args(&["rcop", "--batch", "--fail-fast"])).unwrap();
+        let reports = lint_all(&messages, &parsed).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(!reports[1].valid());
+
+        let mut output = Vec::new();
+        let valid = run(Cursor::new(messages.join("\0")), &parsed, &mut output).unwrap();
+        assert!(!valid);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("nope"));
+        assert!(!output.contains("typo"));
+    }
+
+    #[test]
+    fn test_output_file() {
+        let path = env::temp_dir().join("rcop-test-output-file.json");
+        let path = path.to_str().unwrap();
+        let parsed = parse_args(args(&["rcop", "-f", "json", "--output-file", path])).unwrap();
+        let mut output = Vec::new();
+        assert!(!run_to_file(
+            Cursor::new("feat: add endpoint"),
+            &parsed,
+            path,
+            &mut output
+        )
+        .unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("rcop: invalid, results written to {}\n", path)
+        );
+        let written = fs::read_to_string(path).unwrap();
+        assert!(written.starts_with(r#"{"type":"feat","scope":"""#));
+        assert!(written.contains(r#""rule":"scope-required""#));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_title() {
+        let parsed = parse_args(args(&["rcop", "--title", "rcop/main"])).unwrap();
+        let mut output = Vec::new();
+        assert!(run(Cursor::new("docs: typo"), &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "rcop/main");
+        assert!(lines[1].starts_with("+---"));
+        assert!(lines[2].contains("Type"));
+    }
+
+    #[test]
+    fn test_report_only() {
+        let parsed = parse_args(args(&["rcop"])).unwrap();
+        let mut output = Vec::new();
+        let valid = run(Cursor::new("wip"), &parsed, &mut output).unwrap();
+        assert_eq!(exit_code(&parsed, valid), 1);
+
+        let parsed = parse_args(args(&["rcop", "--report-only"])).unwrap();
+        let mut output = Vec::new();
+        let valid = run(Cursor::new("nope(api): x."), &parsed, &mut output).unwrap();
+        assert!(!valid);
+        assert_eq!(exit_code(&parsed, valid), 0);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("| nope "));
+        assert!(output.contains("Error!: Commit type not allowed"));
+        assert!(output.contains("Warning: Description should not end with a period"));
+    }
+
+    #[test]
+    fn test_exit_zero() {
+        let parsed = parse_args(args(&["rcop", "--exit-zero", "--format", "json"])).unwrap();
+        let mut output = Vec::new();
+        let valid = run(Cursor::new("wip: message"), &parsed, &mut output).unwrap();
+        assert!(!valid);
+        assert_eq!(exit_code(&parsed, valid), 0);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#""diagnostics":[{"#));
+        assert!(output.contains(r#""rule":"type-enum""#));
+    }
+
+    #[test]
+    fn test_stats_categories() {
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--batch",
+            "--stats",
+            "--format",
+            "json",
+            "--categories",
+            "user-facing=feat,fix;internal=chore,ci",
+        ]))
+        .unwrap();
+        let input = "feat(api): add endpoint\0fix(api): handle errors\0chore: bump deps\0docs: typo\0nope: x";
+
+        let reports: Vec<AnalysisResult> = split_batch(input)
+            .iter()
+            .map(|message| analyze(message, &parsed.config))
+            .collect();
+        assert_eq!(reports[0].category, Some("user-facing".to_string()));
+        assert_eq!(reports[3].category, None);
+
+        let summary = stats::collect(&reports);
+        assert_eq!(
+            summary.categories,
+            vec![("user-facing".to_string(), 2), ("internal".to_string(), 1)]
+        );
+
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new(input), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"{"total":5,"valid":4,"invalid":1,"skipped":0,"#,
+                r#""types":{"feat":1,"fix":1,"chore":1,"docs":1,"nope":1},"#,
+                r#""categories":{"user-facing":2,"internal":1}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_changelog() {
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--batch",
+            "--changelog",
+            "--changelog-titles",
+            "fix=Bug Fixes;chore=Maintenance",
+        ]))
+        .unwrap();
+        let input = "feat(api): add endpoint\0fix(api): handle errors\0chore: bump deps\0feat(ui): add button\0nope: x";
+        let mut output = Vec::new();
+        assert!(!run(Cursor::new(input), &parsed, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "## Features\n\n- **api:** add endpoint\n- **ui:** add button\n\n",
+                "## Bug Fixes\n\n- **api:** handle errors\n\n",
+                "## Maintenance\n\n- bump deps\n\n",
+                "Skipped invalid commits:\n- nope: x\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parallel() {
+        let messages: Vec<String> = (0..100)
+            .map(|index| match index % 3 {
+                0 => format!("feat(api): add endpoint {}", index),
+                1 => format!("fix: handle error {}.", index),
+                _ => format!("nope {}", index),
+            })
+            .collect();
+        let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+
+        let sequential = parse_args(args(&["rcop", "--batch"])).unwrap();
+        let parallel = parse_args(args(&["rcop", "--batch", "--parallel"])).unwrap();
+        let expected = lint_all(&messages, &sequential).unwrap();
+        assert_eq!(expected.len(), 100);
+        assert_eq!(lint_all(&messages, &parallel).unwrap(), expected);
+
+        let input = messages.join("\0");
+        let mut sequential_output = Vec::new();
+        let mut parallel_output = Vec::new();
+        assert!(!run(Cursor::new(&input), &sequential, &mut sequential_output).unwrap());
+        assert!(!run(Cursor::new(&input), &parallel, &mut parallel_output).unwrap());
+        assert_eq!(parallel_output, sequential_output);
+
+        let parallel = parse_args(args(&["rcop", "--batch", "--parallel", "--fail-fast"])).unwrap();
+        assert_eq!(lint_all(&messages, &parallel).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let message = "feat(api)!: drop v1\n\nBREAKING CHANGE: v1 is gone";
+        let report = analyze(message, &Config::default());
+        assert!(report.valid());
+        assert_eq!(report.commit.breaking_changes(), vec!["v1 is gone"]);
+
+        let message = "feat(api)!: drop v1\n\nBREAKING CHANGE:";
+        let report = analyze(message, &Config::default());
+        assert_eq!(report.errors()[0].code, "breaking-change-empty");
+        assert_eq!(report.commit.breaking_changes(), vec!["drop v1"]);
+
+        let parsed = parse_args(args(&["rcop", "--format", "json"])).unwrap();
+        let mut output = Vec::new();
+        run(
+            Cursor::new("feat(api)!: drop v1\n\nBREAKING CHANGE: v1 is gone"),
+            &parsed,
+            &mut output,
+        )
+        .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains(r#""breaking":true,"breaking_changes":["v1 is gone"],"#));
+    }
+
+    #[test]
+    fn test_type_order() {
+        let input = "chore: bump deps\0fix(api): handle errors\0docs: typo\0feat(api): add endpoint\0ci: cache";
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--batch",
+            "--changelog",
+            "--type-order",
+            "feat,fix",
+        ]))
+        .unwrap();
+        let mut output = Vec::new();
+        assert!(run(Cursor::new(input), &parsed, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        let titles: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("## "))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                "## Features",
+                "## Fixes",
+                "## chore",
+                "## ci",
+                "## Documentation"
+            ]
+        );
+
+        let parsed = parse_args(args(&[
+            "rcop",
+            "--batch",
+            "--stats",
+            "--format",
+            "json",
+            "--type-order",
+            "feat,fix",
+        ]))
+        .unwrap();
+        let mut output = Vec::new();
+        run(Cursor::new(input), &parsed, &mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains(r#""types":{"feat":1,"fix":1,"chore":1,"ci":1,"docs":1}"#));
+    }
 }
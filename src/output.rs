@@ -0,0 +1,884 @@
+use std::io::{Error, ErrorKind, Write};
+
+use prettytable::{format, row, Table};
+
+#[cfg(test)]
+use crate::commit::Commit;
+use crate::{
+    analysis::AnalysisResult,
+    diagnostic::{Diagnostic, Severity},
+    header::CommitMessage,
+    json::{object, Value},
+    stats::{sort_by_type, Stats},
+    toml,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Table,
+    Github,
+    Json,
+    Markdown,
+    Toml,
+}
+
+pub fn parse_format(text: &str) -> Result<Format, Error> {
+    match text {
+        "table" => Ok(Format::Table),
+        "github" => Ok(Format::Github),
+        "json" => Ok(Format::Json),
+        "markdown" => Ok(Format::Markdown),
+        "toml" => Ok(Format::Toml),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unknown format '{}', expected one of: table, github, json, markdown, toml",
+                text
+            ),
+        )),
+    }
+}
+
+pub fn render_table<W: Write>(writer: &mut W, reports: &[AnalysisResult]) -> Result<(), Error> {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_DEFAULT);
+    table.set_titles(row!["Type", "Scope", "Description", "Body", "Valid"]);
+    for report in reports {
+        table.add_row(row![
+            report.commit.commit_type,
+            report.commit.scope,
+            report.commit.description,
+            report.commit.body,
+            report.status()
+        ]);
+    }
+    table.print(writer)?;
+    for (index, report) in reports.iter().enumerate() {
+        for diagnostic in &report.diagnostics {
+            let prefix = match diagnostic.severity {
+                Severity::Error => "Error!",
+                Severity::Warning => "Warning",
+            };
+            if reports.len() > 1 {
+                writeln!(
+                    writer,
+                    "{} (#{}): {}",
+                    prefix,
+                    index + 1,
+                    diagnostic.message
+                )?;
+            } else {
+                writeln!(writer, "{}: {}", prefix, diagnostic.message)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Cells can't span lines, and a pipe would end the cell.
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// One line per commit, its header with a check mark, or a cross and the
+/// first error. Skipped commits are marked with the reason.
+pub fn render_compact<W: Write>(
+    writer: &mut W,
+    headers: &[&str],
+    reports: &[AnalysisResult],
+) -> Result<(), Error> {
+    for (header, report) in headers.iter().zip(reports) {
+        match (&report.skipped, report.errors().first()) {
+            (Some(reason), _) => writeln!(writer, "- {} ({})", header, reason)?,
+            (None, None) => writeln!(writer, "✓ {}", header)?,
+            (None, Some(error)) => writeln!(writer, "✗ {} - {}", header, error.message)?,
+        }
+    }
+    Ok(())
+}
+
+// The section titles of the types conventional-changelog knows, the other
+// types use the type itself.
+const CHANGELOG_TITLES: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Fixes"),
+    ("perf", "Performance"),
+    ("revert", "Reverts"),
+    ("docs", "Documentation"),
+    ("refactor", "Refactoring"),
+];
+
+fn changelog_title<'a>(commit_type: &'a str, titles: &'a [(String, String)]) -> &'a str {
+    titles
+        .iter()
+        .find(|(name, _)| name == commit_type)
+        .map(|(_, title)| title.as_str())
+        .or_else(|| {
+            CHANGELOG_TITLES
+                .iter()
+                .find(|(name, _)| *name == commit_type)
+                .map(|(_, title)| *title)
+        })
+        .unwrap_or(commit_type)
+}
+
+/// Groups the valid commits by their type into sections of a changelog, in
+/// the given order of the types, or in the order they first appear. Invalid
+/// commits are listed at the end, skipped ones are left out.
+pub fn render_changelog<W: Write>(
+    writer: &mut W,
+    headers: &[&str],
+    reports: &[AnalysisResult],
+    titles: &[(String, String)],
+    order: &[String],
+) -> Result<(), Error> {
+    // A section is ordered by the first type that has its title.
+    let mut sections: Vec<(&str, &str, Vec<&AnalysisResult>)> = vec![];
+    for report in reports
+        .iter()
+        .filter(|report| report.skipped.is_none() && report.valid())
+    {
+        let title = changelog_title(&report.commit.commit_type, titles);
+        match sections
+            .iter_mut()
+            .find(|(_, existing, _)| *existing == title)
+        {
+            Some((_, _, commits)) => commits.push(report),
+            None => sections.push((&report.commit.commit_type, title, vec![report])),
+        }
+    }
+    sort_by_type(&mut sections, order, |(commit_type, _, _)| commit_type);
+    for (index, (_, title, commits)) in sections.iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "## {}\n", title)?;
+        for report in commits {
+            match report.commit.scope.as_str() {
+                "" => writeln!(writer, "- {}", report.commit.description)?,
+                scope => writeln!(writer, "- **{}:** {}", scope, report.commit.description)?,
+            }
+        }
+    }
+    let invalid: Vec<&&str> = headers
+        .iter()
+        .zip(reports)
+        .filter(|(_, report)| report.skipped.is_none() && !report.valid())
+        .map(|(header, _)| header)
+        .collect();
+    if !invalid.is_empty() {
+        if !sections.is_empty() {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "Skipped invalid commits:")?;
+        for header in invalid {
+            writeln!(writer, "- {}", header)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn render_markdown<W: Write>(writer: &mut W, reports: &[AnalysisResult]) -> Result<(), Error> {
+    writeln!(writer, "| Type | Scope | Description | Body | Valid |")?;
+    writeln!(writer, "| --- | --- | --- | --- | --- |")?;
+    for report in reports {
+        let valid = match (&report.skipped, report.valid()) {
+            (Some(reason), _) => reason.to_string(),
+            (None, true) => "✅".to_string(),
+            (None, false) => "❌".to_string(),
+        };
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} | {} |",
+            escape_markdown_cell(&report.commit.commit_type),
+            escape_markdown_cell(&report.commit.scope),
+            escape_markdown_cell(&report.commit.description),
+            escape_markdown_cell(&report.commit.body),
+            valid
+        )?;
+    }
+    let diagnostics: Vec<(usize, &Diagnostic)> = reports
+        .iter()
+        .enumerate()
+        .flat_map(|(index, report)| report.diagnostics.iter().map(move |d| (index, d)))
+        .collect();
+    if !diagnostics.is_empty() {
+        writeln!(writer)?;
+    }
+    for (index, diagnostic) in diagnostics {
+        let prefix = match diagnostic.severity {
+            Severity::Error => "**Error**",
+            Severity::Warning => "**Warning**",
+        };
+        if reports.len() > 1 {
+            writeln!(
+                writer,
+                "- {} (#{}): {}",
+                prefix,
+                index + 1,
+                diagnostic.message
+            )?;
+        } else {
+            writeln!(writer, "- {}: {}", prefix, diagnostic.message)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn render_diff<W: Write>(writer: &mut W, original: &str, suggested: &str) -> Result<(), Error> {
+    writeln!(writer, "--- original")?;
+    writeln!(writer, "+++ suggested")?;
+    writeln!(writer, "-{}", original)?;
+    writeln!(writer, "+{}", suggested)?;
+    Ok(())
+}
+
+// GitHub workflow commands are line based, so the data has to be escaped
+// according to https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts
+fn escape_workflow_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+pub fn render_github<W: Write>(writer: &mut W, diagnostics: &[Diagnostic]) -> Result<(), Error> {
+    for diagnostic in diagnostics {
+        writeln!(
+            writer,
+            "::{}::{}",
+            severity_name(diagnostic.severity),
+            escape_workflow_data(&diagnostic.message)
+        )?;
+    }
+    Ok(())
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+// Tokens can repeat, e.g. for several co-authors, so every token maps to the
+// list of its values.
+fn footers_json(footers: &[(String, String)]) -> Value {
+    let mut entries: Vec<(String, Value)> = vec![];
+    for (token, value) in footers {
+        match entries.iter_mut().find(|(existing, _)| existing == token) {
+            Some((_, Value::Array(values))) => values.push(Value::from(value.as_str())),
+            _ => entries.push((
+                token.to_string(),
+                Value::Array(vec![Value::from(value.as_str())]),
+            )),
+        }
+    }
+    Value::Object(entries)
+}
+
+fn span_json(span: Option<(usize, usize)>) -> Value {
+    match span {
+        Some((start, end)) => object(vec![
+            ("start", Value::from(start)),
+            ("end", Value::from(end)),
+        ]),
+        None => Value::Null,
+    }
+}
+
+fn report_json(report: &AnalysisResult) -> Value {
+    object(vec![
+        ("type", Value::from(report.commit.commit_type.as_str())),
+        ("scope", Value::from(report.commit.scope.as_str())),
+        (
+            "description",
+            Value::from(report.commit.description.as_str()),
+        ),
+        ("body", Value::from(report.commit.body.as_str())),
+        ("footers", footers_json(&report.commit.footers)),
+        ("breaking", Value::from(report.commit.breaking)),
+        (
+            "breaking_changes",
+            strings_json(&report.commit.breaking_changes()),
+        ),
+        ("category", Value::from(report.category.as_deref())),
+        ("valid", Value::from(report.valid())),
+        ("skipped", Value::from(report.skipped.as_deref())),
+        (
+            "diagnostics",
+            Value::Array(
+                report
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| {
+                        object(vec![
+                            ("rule", Value::from(diagnostic.code)),
+                            ("severity", Value::from(severity_name(diagnostic.severity))),
+                            ("message", Value::from(diagnostic.message.as_str())),
+                            ("span", span_json(diagnostic.span)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "rules",
+            Value::Array(
+                report
+                    .outcomes()
+                    .into_iter()
+                    .map(|(rule, diagnostic)| {
+                        object(vec![
+                            ("id", Value::from(rule)),
+                            (
+                                "severity",
+                                Value::from(
+                                    diagnostic.map(|diagnostic| severity_name(diagnostic.severity)),
+                                ),
+                            ),
+                            ("passed", Value::from(diagnostic.is_none())),
+                            (
+                                "message",
+                                Value::from(
+                                    diagnostic.map(|diagnostic| diagnostic.message.as_str()),
+                                ),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+// A single commit is rendered as an object, a batch as an array of objects.
+// A title is added as a top-level field, so a titled batch is wrapped in an
+// object holding the commits.
+pub fn render_json<W: Write>(
+    writer: &mut W,
+    reports: &[AnalysisResult],
+    batch: bool,
+    title: Option<&str>,
+) -> Result<(), Error> {
+    let value = match (batch, title) {
+        (true, None) => Value::Array(reports.iter().map(report_json).collect()),
+        (true, Some(title)) => object(vec![
+            ("title", Value::from(title)),
+            (
+                "commits",
+                Value::Array(reports.iter().map(report_json).collect()),
+            ),
+        ]),
+        (false, None) => report_json(&reports[0]),
+        (false, Some(title)) => match report_json(&reports[0]) {
+            Value::Object(mut entries) => {
+                entries.insert(0, ("title".to_string(), Value::from(title)));
+                Value::Object(entries)
+            }
+            value => value,
+        },
+    };
+    writeln!(writer, "{}", value)?;
+    Ok(())
+}
+
+/// Renders an error that kept a message from being validated, like input
+/// that isn't UTF-8, as a JSON object with an `error` field.
+pub fn render_json_error<W: Write>(writer: &mut W, error: &Error) -> Result<(), Error> {
+    let value = object(vec![
+        ("error", Value::from(error.to_string().as_str())),
+        ("valid", Value::from(false)),
+    ]);
+    writeln!(writer, "{}", value)?;
+    Ok(())
+}
+
+/// Like `render_json`, but a batch is a `[[commits]]` array of tables, as a
+/// TOML document can't be an array.
+pub fn render_toml<W: Write>(
+    writer: &mut W,
+    reports: &[AnalysisResult],
+    batch: bool,
+    title: Option<&str>,
+) -> Result<(), Error> {
+    let mut entries = vec![];
+    if let Some(title) = title {
+        entries.push(("title".to_string(), Value::from(title)));
+    }
+    match (batch, reports.first().map(report_json)) {
+        (false, Some(Value::Object(report))) => entries.extend(report),
+        _ => entries.push((
+            "commits".to_string(),
+            Value::Array(reports.iter().map(report_json).collect()),
+        )),
+    }
+    write!(writer, "{}", toml::to_string(&entries))?;
+    Ok(())
+}
+
+fn counts_json(counts: &[(String, usize)]) -> Value {
+    Value::Object(
+        counts
+            .iter()
+            .map(|(key, count)| (key.to_string(), Value::from(*count)))
+            .collect(),
+    )
+}
+
+pub fn render_stats<W: Write>(writer: &mut W, stats: &Stats, format: Format) -> Result<(), Error> {
+    if format == Format::Json {
+        let value = object(vec![
+            ("total", Value::from(stats.total)),
+            ("valid", Value::from(stats.valid)),
+            ("invalid", Value::from(stats.invalid)),
+            ("skipped", Value::from(stats.skipped)),
+            ("types", counts_json(&stats.types)),
+            ("categories", counts_json(&stats.categories)),
+        ]);
+        writeln!(writer, "{}", value)?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_DEFAULT);
+    table.set_titles(row!["Total", "Valid", "Invalid", "Skipped"]);
+    table.add_row(row![stats.total, stats.valid, stats.invalid, stats.skipped]);
+    table.print(writer)?;
+    for (title, counts) in [("Type", &stats.types), ("Category", &stats.categories)] {
+        if counts.is_empty() {
+            continue;
+        }
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_DEFAULT);
+        table.set_titles(row![title, "Commits"]);
+        for (key, count) in counts {
+            table.add_row(row![key, count]);
+        }
+        table.print(writer)?;
+    }
+    Ok(())
+}
+
+fn strings_json(values: &[String]) -> Value {
+    Value::Array(
+        values
+            .iter()
+            .map(|value| Value::from(value.as_str()))
+            .collect(),
+    )
+}
+
+pub fn render_types<W: Write>(
+    writer: &mut W,
+    commit_types: &[CommitMessage],
+    format: Format,
+) -> Result<(), Error> {
+    if format == Format::Json {
+        let value = Value::Array(
+            commit_types
+                .iter()
+                .map(|commit_type| {
+                    object(vec![
+                        ("type", Value::from(commit_type.commit_type.as_str())),
+                        ("required", strings_json(&commit_type.required)),
+                        ("forbidden", strings_json(&commit_type.forbidden)),
+                        ("scopes", strings_json(&commit_type.scopes)),
+                        ("category", Value::from(commit_type.category.as_deref())),
+                        ("help", Value::from(commit_type.help.as_deref())),
+                    ])
+                })
+                .collect(),
+        );
+        writeln!(writer, "{}", value)?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![
+        "Type",
+        "Required",
+        "Forbidden",
+        "Scopes",
+        "Category",
+        "Help"
+    ]);
+    for commit_type in commit_types {
+        table.add_row(row![
+            commit_type.commit_type,
+            commit_type.required.join(", "),
+            commit_type.forbidden.join(", "),
+            commit_type.scopes.join(", "),
+            commit_type.category.as_deref().unwrap_or(""),
+            commit_type.help.as_deref().unwrap_or("")
+        ]);
+    }
+    table.print(writer)?;
+    Ok(())
+}
+
+#[test]
+fn test_parse_format() {
+    assert_eq!(parse_format("table").unwrap(), Format::Table);
+    assert_eq!(parse_format("github").unwrap(), Format::Github);
+    assert_eq!(parse_format("json").unwrap(), Format::Json);
+    assert_eq!(parse_format("markdown").unwrap(), Format::Markdown);
+    assert_eq!(parse_format("toml").unwrap(), Format::Toml);
+    assert_eq!(
+        parse_format("yaml").unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_render_github() {
+    let diagnostics = vec![
+        Diagnostic::error("type-enum", "Commit type not allowed"),
+        Diagnostic::warning("trailing-period", "Description ends with a period"),
+    ];
+    let mut output = Vec::new();
+    render_github(&mut output, &diagnostics).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "::error::Commit type not allowed\n::warning::Description ends with a period\n"
+    );
+}
+
+#[test]
+fn test_render_github_escapes_newlines() {
+    let mut output = Vec::new();
+    render_github(
+        &mut output,
+        &[Diagnostic::error("header-format", "100% wrong\nreally")],
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "::error::100%25 wrong%0Areally\n"
+    );
+}
+
+#[test]
+fn test_render_json() {
+    let report = AnalysisResult {
+        commit: Commit {
+            commit_type: "feat".to_string(),
+            scope: "api".to_string(),
+            description: "add endpoint.".to_string(),
+            ..Commit::default()
+        },
+        diagnostics: vec![Diagnostic::warning(
+            "trailing-period",
+            "Description should not end with a period",
+        )],
+        category: Some("user-facing".to_string()),
+        ..AnalysisResult::default()
+    };
+    let mut output = Vec::new();
+    render_json(&mut output, &[report], false, None).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        concat!(
+            r#"{"type":"feat","scope":"api","description":"add endpoint.","body":"","footers":{},"breaking":false,"breaking_changes":[],"#,
+            r#""category":"user-facing","valid":true,"skipped":null,"diagnostics":["#,
+            r#"{"rule":"trailing-period","severity":"warning","message":"Description should not end with a period","span":null}],"#,
+            r#""rules":[{"id":"trailing-period","severity":"warning","passed":false,"message":"Description should not end with a period"}]}"#,
+            "\n"
+        )
+    );
+}
+
+#[test]
+fn test_render_json_rules() {
+    let report = AnalysisResult {
+        diagnostics: vec![Diagnostic::error("scope-required", "Scope required")],
+        checked: vec!["type-enum", "scope-required"],
+        ..AnalysisResult::default()
+    };
+    let mut output = Vec::new();
+    render_json(&mut output, &[report], false, None).unwrap();
+    assert!(String::from_utf8(output).unwrap().ends_with(concat!(
+        r#""rules":[{"id":"type-enum","severity":null,"passed":true,"message":null},"#,
+        r#"{"id":"scope-required","severity":"error","passed":false,"message":"Scope required"}]}"#,
+        "\n"
+    )));
+}
+
+#[test]
+fn test_render_json_error() {
+    let mut output = Vec::new();
+    render_json_error(
+        &mut output,
+        &Error::new(std::io::ErrorKind::InvalidData, "not \"UTF-8\""),
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "{\"error\":\"not \\\"UTF-8\\\"\",\"valid\":false}\n"
+    );
+}
+
+#[test]
+fn test_render_json_title() {
+    let reports = vec![AnalysisResult::default()];
+    let mut output = Vec::new();
+    render_json(&mut output, &reports, false, Some("rcop")).unwrap();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .starts_with(r#"{"title":"rcop","type":"#));
+
+    let mut output = Vec::new();
+    render_json(&mut output, &reports, true, Some("rcop")).unwrap();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .starts_with(r#"{"title":"rcop","commits":[{"type":"#));
+}
+
+#[test]
+fn test_render_json_footers() {
+    let report = AnalysisResult {
+        commit: Commit::new(
+            (
+                "feat".to_string(),
+                "api".to_string(),
+                "add endpoint".to_string(),
+                false,
+            ),
+            "Refs: #1\nCo-authored-by: Jane <jane@example.com>\nCo-authored-by: John <john@example.com>",
+        ),
+        ..AnalysisResult::default()
+    };
+    let mut output = Vec::new();
+    render_json(&mut output, &[report], false, None).unwrap();
+    assert!(String::from_utf8(output).unwrap().contains(concat!(
+        r##""footers":{"Refs":["#1"],"##,
+        r#""Co-authored-by":["Jane <jane@example.com>","John <john@example.com>"]}"#
+    )));
+}
+
+#[test]
+fn test_render_markdown() {
+    let report = AnalysisResult {
+        commit: Commit {
+            commit_type: "feat".to_string(),
+            scope: "api".to_string(),
+            description: "add a | b".to_string(),
+            body: "first\nsecond".to_string(),
+            ..Commit::default()
+        },
+        ..AnalysisResult::default()
+    };
+    let mut output = Vec::new();
+    render_markdown(&mut output, &[report]).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "| Type | Scope | Description | Body | Valid |",
+            "| --- | --- | --- | --- | --- |",
+            "| feat | api | add a \\| b | first<br>second | ✅ |",
+        ]
+    );
+}
+
+#[test]
+fn test_render_markdown_diagnostics() {
+    let reports = vec![
+        AnalysisResult::default(),
+        AnalysisResult {
+            diagnostics: vec![Diagnostic::error("type-enum", "Commit type not allowed")],
+            ..AnalysisResult::default()
+        },
+    ];
+    let mut output = Vec::new();
+    render_markdown(&mut output, &reports).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("|  |  |  |  | ❌ |"));
+    assert!(output.ends_with("\n\n- **Error** (#2): Commit type not allowed\n"));
+}
+
+#[test]
+fn test_render_toml() {
+    let report = AnalysisResult {
+        commit: Commit {
+            commit_type: "feat".to_string(),
+            scope: "api".to_string(),
+            description: "add endpoint".to_string(),
+            footers: vec![("Closes".to_string(), "#12".to_string())],
+            ..Commit::default()
+        },
+        diagnostics: vec![Diagnostic::warning("trailing-period", "No period")],
+        ..AnalysisResult::default()
+    };
+    let mut output = Vec::new();
+    render_toml(&mut output, std::slice::from_ref(&report), false, None).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        r##"type = "feat"
+scope = "api"
+description = "add endpoint"
+body = ""
+breaking = false
+breaking_changes = []
+valid = true
+
+[footers]
+Closes = ["#12"]
+
+[[diagnostics]]
+rule = "trailing-period"
+severity = "warning"
+message = "No period"
+
+[[rules]]
+id = "trailing-period"
+severity = "warning"
+passed = false
+message = "No period"
+"##
+    );
+
+    let mut output = Vec::new();
+    render_toml(
+        &mut output,
+        &[report.clone(), report],
+        true,
+        Some("Release"),
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("title = \"Release\"\n\n[[commits]]\ntype = \"feat\"\n"));
+    assert_eq!(output.matches("[[commits]]").count(), 2);
+    assert_eq!(output.matches("[commits.footers]").count(), 2);
+    assert_eq!(output.matches("[[commits.diagnostics]]").count(), 2);
+}
+
+#[test]
+fn test_render_changelog() {
+    let commit = |commit_type: &str, scope: &str, description: &str| AnalysisResult {
+        commit: Commit {
+            commit_type: commit_type.to_string(),
+            scope: scope.to_string(),
+            description: description.to_string(),
+            ..Commit::default()
+        },
+        ..AnalysisResult::default()
+    };
+    let reports = vec![
+        commit("fix", "", "handle timeouts"),
+        commit("feat", "api", "add endpoint"),
+        commit("chore", "", "bump deps"),
+        commit("fix", "ui", "align button"),
+        AnalysisResult {
+            diagnostics: vec![Diagnostic::error("type-enum", "Commit type not allowed")],
+            ..commit("wip", "", "stuff")
+        },
+        AnalysisResult {
+            skipped: Some("ignored".to_string()),
+            ..AnalysisResult::default()
+        },
+    ];
+    let headers = [
+        "fix: handle timeouts",
+        "feat(api): add endpoint",
+        "chore: bump deps",
+        "fix(ui): align button",
+        "wip: stuff",
+        "Merge branch 'main'",
+    ];
+    let mut output = Vec::new();
+    render_changelog(
+        &mut output,
+        &headers,
+        &reports,
+        &[("chore".to_string(), "Maintenance".to_string())],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "## Fixes\n\n- handle timeouts\n- **ui:** align button\n\n## Features\n\n- **api:** add endpoint\n\n## Maintenance\n\n- bump deps\n\nSkipped invalid commits:\n- wip: stuff\n"
+    );
+
+    let mut output = Vec::new();
+    render_changelog(&mut output, &headers[2..3], &reports[2..3], &[], &[]).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "## chore\n\n- bump deps\n"
+    );
+
+    let mut output = Vec::new();
+    render_changelog(
+        &mut output,
+        &headers[..4],
+        &reports[..4],
+        &[],
+        &["feat".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "## Features\n\n- **api:** add endpoint\n\n## chore\n\n- bump deps\n\n## Fixes\n\n- handle timeouts\n- **ui:** align button\n"
+    );
+}
+
+#[test]
+fn test_render_compact() {
+    let reports = vec![
+        AnalysisResult::default(),
+        AnalysisResult {
+            diagnostics: vec![
+                Diagnostic::warning(
+                    "trailing-period",
+                    "Description should not end with a period",
+                ),
+                Diagnostic::error(
+                    "scope-required",
+                    "Commit type requires a scope, but none given",
+                ),
+            ],
+            ..AnalysisResult::default()
+        },
+        AnalysisResult {
+            skipped: Some("ignored".to_string()),
+            ..AnalysisResult::default()
+        },
+    ];
+    let mut output = Vec::new();
+    render_compact(
+        &mut output,
+        &[
+            "feat(api): add thing",
+            "feat: add thing.",
+            "Merge branch 'main'",
+        ],
+        &reports,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "✓ feat(api): add thing\n✗ feat: add thing. - Commit type requires a scope, but none given\n- Merge branch 'main' (ignored)\n"
+    );
+}
+
+#[test]
+fn test_render_types_json() {
+    let commit_types = vec![CommitMessage {
+        commit_type: "chore".to_string(),
+        required: vec!["description".to_string()],
+        forbidden: vec!["scope".to_string()],
+        category: Some("internal".to_string()),
+        help: Some("Maintenance".to_string()),
+        ..Default::default()
+    }];
+    let mut output = Vec::new();
+    render_types(&mut output, &commit_types, Format::Json).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        r#"[{"type":"chore","required":["description"],"forbidden":["scope"],"scopes":[],"category":"internal","help":"Maintenance"}]"#.to_string() + "\n"
+    );
+}
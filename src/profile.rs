@@ -0,0 +1,398 @@
+use std::io::{Error, ErrorKind};
+
+// The options that make up a `Config`, and can therefore be set in a config
+// file, with the JSON type of their value. Every key is the name of the
+// command line option without the dashes.
+const KEYS: &[(&str, &str)] = &[
+    ("types", "string"),
+    ("ignore-case", "boolean"),
+    ("fuzzy-type", "boolean"),
+    ("require-type-in", "string"),
+    ("only-types", "string"),
+    ("breaking-allowed-for", "string"),
+    ("disable-rules", "string"),
+    ("severity-overrides", "string"),
+    ("categories", "string"),
+    ("type-help", "string"),
+    ("no-scope-for", "string"),
+    ("require-description-for", "string"),
+    ("scope-types", "string"),
+    ("type-scopes", "string"),
+    ("scope-case", "string"),
+    ("empty-scope-parens", "string"),
+    ("warn-empty-scope-parens", "boolean"),
+    ("require-lowercase-scope-for", "string"),
+    ("deny-scopes", "string"),
+    ("scopes-file", "string"),
+    ("scopes-from-codeowners", "string"),
+    ("sort-scopes", "boolean"),
+    ("warn-mixed-scope-separators", "boolean"),
+    ("allow-at-in-scope", "boolean"),
+    ("subject-case", "string"),
+    ("max-description-length", "integer"),
+    ("length-excludes-ticket", "boolean"),
+    ("max-message-bytes", "integer"),
+    ("max-body-lines", "integer"),
+    ("max-consecutive-blank-lines", "integer"),
+    ("description-pattern", "string"),
+    ("prefix-pattern", "string"),
+    ("placeholder-patterns", "string"),
+    ("strict-separator-spacing", "boolean"),
+    ("warn-url-only-description", "boolean"),
+    ("warn-redundant-type", "boolean"),
+    ("warn-non-imperative", "boolean"),
+    ("non-imperative-suffixes", "string"),
+    ("imperative-exceptions", "string"),
+    ("body-mentions-scope", "boolean"),
+    ("require-revert-sha", "boolean"),
+    ("require-release-version", "boolean"),
+    ("release-commit", "string"),
+    ("warn-trailing-issue-ref", "boolean"),
+    ("warn-trailing-ellipsis", "boolean"),
+    ("require-capitalized-footer-tokens", "boolean"),
+    ("allowed-footers", "string"),
+    ("reviewers-file", "string"),
+    ("require-footer-blank-line", "boolean"),
+    ("issue-footer-style", "string"),
+    ("forbidden-body-words", "string"),
+    ("require-body-sections", "string"),
+    ("allowed-verbs", "string"),
+    ("ascii-only-subject", "boolean"),
+    ("encoding-check", "boolean"),
+    ("squash-aware", "boolean"),
+];
+
+// The name of a profile, or none for the options shared by all of them, and
+// the arguments it stands for.
+type Section = (Option<String>, Vec<String>);
+
+fn invalid(line: usize, message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid config file, line {}: {}", line + 1, message),
+    )
+}
+
+/// Parses a config file into its sections, each holding the equivalent
+/// command line arguments. The keys before the first `[profile.NAME]`
+/// header belong to the unnamed section.
+pub(crate) fn parse(text: &str) -> Result<Vec<Section>, Error> {
+    let mut sections: Vec<Section> = vec![(None, vec![])];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = header
+                .trim()
+                .strip_prefix("profile.")
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| invalid(index, &format!("expected [profile.NAME], got {}", line)))?;
+            if sections
+                .iter()
+                .any(|(existing, _)| existing.as_deref() == Some(name))
+            {
+                return Err(invalid(
+                    index,
+                    &format!("the profile '{}' is defined more than once", name),
+                ));
+            }
+            sections.push((Some(name.to_string()), vec![]));
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(index, &format!("expected KEY = VALUE, got {}", line)))?;
+        let key = key.trim();
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let (_, args) = sections.last_mut().unwrap();
+        if let Some(name) = key.strip_prefix('$') {
+            args.push("--scope-sets".to_string());
+            args.push(format!("{}={}", name, value));
+            continue;
+        }
+        if !KEYS.iter().any(|(name, _)| *name == key) {
+            return Err(invalid(index, &format!("unknown key '{}'", key)));
+        }
+        let boolean = KEYS
+            .iter()
+            .any(|(name, value_type)| *name == key && *value_type == "boolean");
+        match value {
+            "true" => args.push(format!("--{}", key)),
+            // Kept, so a later file or a profile can switch off a flag.
+            "false" if boolean => args.push(format!("--no-{}", key)),
+            "false" => {}
+            value => {
+                args.push(format!("--{}", key));
+                args.push(value.to_string());
+            }
+        }
+    }
+    Ok(sections)
+}
+
+/// A JSON Schema of the config file, for editors that validate it. The
+/// profiles take the same options as the unnamed section.
+pub(crate) fn schema() -> String {
+    let properties = KEYS
+        .iter()
+        .map(|(name, value_type)| format!(r#""{}":{{"type":"{}"}}"#, name, value_type))
+        .collect::<Vec<String>>()
+        .join(",");
+    let scope_sets = r#""patternProperties":{"^\\$":{"type":"string","description":"A scope set, e.g. $frontend = ui,web"}},"additionalProperties":false"#;
+    let profile =
+        r##""profile":{"type":"object","additionalProperties":{"$ref":"#/definitions/options"}}"##;
+    format!(
+        r##"{{"$schema":"http://json-schema.org/draft-07/schema#","title":"rcop config","type":"object","properties":{{{},{}}},{},"definitions":{{"options":{{"type":"object","properties":{{{}}},{}}}}}}}"##,
+        profile, properties, scope_sets, properties, scope_sets
+    )
+}
+
+// Types given in several files are merged by name, so a later file can
+// change or add single types without repeating all of them.
+fn merge_types(merged: &mut Vec<String>, spec: &str) {
+    for item in spec.split(';').filter(|item| !item.trim().is_empty()) {
+        let name = item.split('=').next().unwrap_or("").trim();
+        match merged
+            .iter_mut()
+            .find(|existing| existing.split('=').next().unwrap_or("").trim() == name)
+        {
+            Some(existing) => *existing = item.to_string(),
+            None => merged.push(item.to_string()),
+        }
+    }
+}
+
+/// Returns the arguments of the config files in the order they are given.
+/// Every file contributes its unnamed section, followed by the selected
+/// profile if it defines it. As later arguments override earlier ones,
+/// later files take precedence, except for the types which are merged.
+pub(crate) fn layer(files: &[Vec<Section>], profile: Option<&str>) -> Result<Vec<String>, Error> {
+    let mut args = vec![];
+    let mut found = false;
+    for sections in files {
+        args.extend(sections[0].1.iter().cloned());
+        if let Some((_, profile_args)) = sections
+            .iter()
+            .find(|(name, _)| profile.is_some() && name.as_deref() == profile)
+        {
+            found = true;
+            args.extend(profile_args.iter().cloned());
+        }
+    }
+    if let (Some(profile), false) = (profile, found) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The profile '{}' is not defined in the config file",
+                profile
+            ),
+        ));
+    }
+
+    let mut types: Option<Vec<String>> = None;
+    let mut layered = vec![];
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--types" {
+            merge_types(
+                types.get_or_insert_with(Vec::new),
+                &iter.next().unwrap_or_default(),
+            );
+        } else {
+            layered.push(arg);
+        }
+    }
+    if let Some(types) = types {
+        layered.insert(0, types.join(";"));
+        layered.insert(0, "--types".to_string());
+    }
+    Ok(resolve_flags(layered))
+}
+
+// A boolean is a scalar like any other option, the last `--KEY` or
+// `--no-KEY` wins. Only the flags that end up switched on are passed on.
+fn resolve_flags(args: Vec<String>) -> Vec<String> {
+    let flag = |arg: &str| -> Option<(String, bool)> {
+        let name = arg.strip_prefix("--")?;
+        let (name, on) = match name.strip_prefix("no-") {
+            Some(name) => (name, false),
+            None => (name, true),
+        };
+        KEYS.iter()
+            .any(|(key, value_type)| *key == name && *value_type == "boolean")
+            .then(|| (name.to_string(), on))
+    };
+    // Values are skipped, so a value that looks like a flag is left alone.
+    let mut positions = vec![];
+    let mut index = 0;
+    while index < args.len() {
+        match flag(&args[index]) {
+            Some(found) => {
+                positions.push((index, found));
+                index += 1;
+            }
+            None => index += 2,
+        }
+    }
+    let mut resolved = vec![];
+    for (index, arg) in args.iter().enumerate() {
+        match positions.iter().find(|(position, _)| *position == index) {
+            Some((_, (name, on))) => {
+                let last = positions
+                    .iter()
+                    .rev()
+                    .find(|(_, (other, _))| other == name)
+                    .map(|(position, _)| *position);
+                if *on && last == Some(index) {
+                    resolved.push(arg.to_string());
+                }
+            }
+            None => resolved.push(arg.to_string()),
+        }
+    }
+    resolved
+}
+
+#[test]
+fn test_parse() {
+    let text = r#"
+# shared by all profiles
+types = "feat=scope,description;fix=description"
+
+[profile.strict]
+scope-case = kebab
+squash-aware = true
+ignore-case = false
+"#;
+    assert_eq!(
+        parse(text).unwrap(),
+        vec![
+            (
+                None,
+                vec![
+                    "--types".to_string(),
+                    "feat=scope,description;fix=description".to_string()
+                ]
+            ),
+            (
+                Some("strict".to_string()),
+                vec![
+                    "--scope-case".to_string(),
+                    "kebab".to_string(),
+                    "--squash-aware".to_string(),
+                    "--no-ignore-case".to_string()
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_scope_sets() {
+    assert_eq!(
+        parse("$frontend = ui,web,mobile\ntypes = feat=scope:$frontend").unwrap()[0].1,
+        vec![
+            "--scope-sets",
+            "frontend=ui,web,mobile",
+            "--types",
+            "feat=scope:$frontend"
+        ]
+    );
+}
+
+#[test]
+fn test_parse_failure() {
+    for text in [
+        "types",
+        "format = json",
+        "[strict]",
+        "[profile.]",
+        "[profile.a]\n[profile.a]",
+    ] {
+        assert_eq!(
+            parse(text).unwrap_err().kind(),
+            ErrorKind::InvalidData,
+            "Should have failed for '{}'",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_layer() {
+    let files = vec![parse("ignore-case = true\n[profile.strict]\nsquash-aware = true").unwrap()];
+    assert_eq!(layer(&files, None).unwrap(), vec!["--ignore-case"]);
+    assert_eq!(
+        layer(&files, Some("strict")).unwrap(),
+        vec!["--ignore-case", "--squash-aware"]
+    );
+    assert!(layer(&files, Some("relaxed")).is_err());
+}
+
+#[test]
+fn test_layer_profile_switches_off_flag() {
+    let files = vec![parse(
+        "squash-aware = true\nignore-case = true\n[profile.relaxed]\nsquash-aware = false",
+    )
+    .unwrap()];
+    assert_eq!(
+        layer(&files, None).unwrap(),
+        vec!["--squash-aware", "--ignore-case"]
+    );
+    assert_eq!(
+        layer(&files, Some("relaxed")).unwrap(),
+        vec!["--ignore-case"]
+    );
+}
+
+#[test]
+fn test_layer_merges_files() {
+    let base = parse(
+        "types = feat=scope,description;fix=scope,description\nmax-description-length = 50\nscope-case = kebab\nignore-case = true",
+    )
+    .unwrap();
+    let repo = parse(
+        "types = fix=description;docs=description\nmax-description-length = 72\nignore-case = false\n[profile.strict]\nsquash-aware = true",
+    )
+    .unwrap();
+    assert_eq!(
+        layer(&[base, repo], Some("strict")).unwrap(),
+        vec![
+            "--types",
+            "feat=scope,description;fix=description;docs=description",
+            "--max-description-length",
+            "50",
+            "--scope-case",
+            "kebab",
+            "--max-description-length",
+            "72",
+            "--squash-aware",
+        ]
+    );
+}
+
+#[test]
+fn test_schema() {
+    let schema = schema();
+    assert!(schema.starts_with(r#"{"$schema":"http://json-schema.org/draft-07/schema#","title":"rcop config","type":"object","properties":{"profile":{"#));
+    for (name, value_type) in [
+        ("types", "string"),
+        ("scope-types", "string"),
+        ("max-description-length", "integer"),
+        ("ignore-case", "boolean"),
+    ] {
+        assert!(
+            schema.contains(&format!(r#""{}":{{"type":"{}"}}"#, name, value_type)),
+            "Missing '{}' in the schema",
+            name
+        );
+    }
+    assert!(schema.contains(r#""patternProperties":{"^\\$":"#));
+    assert!(schema.ends_with(r#""additionalProperties":false}}}"#));
+}
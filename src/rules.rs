@@ -0,0 +1,1761 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    commit::Commit,
+    config::{Config, EmptyScopeParens, IssueFooterStyle, Pattern, ScopeCase, SubjectCase},
+    diagnostic::Diagnostic,
+    footer::{self, is_breaking_token},
+};
+
+lazy_static! {
+    static ref KEBAB_CASE: Regex = Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?$").unwrap();
+    static ref TRAILING_ISSUE_REF: Regex = Regex::new(r"(?:^|\s)\(?(#\d+)\)?$").unwrap();
+    static ref CO_AUTHOR: Regex = Regex::new(r"^.+ <[^<>@\s]+@[^<>@\s]+\.[^<>@\s]+>$").unwrap();
+    static ref ISSUE_FOOTER: Regex =
+        Regex::new(r"^(?i:(close[sd]?|fix(?:e[sd])?|resolve[sd]?|refs?))(: | #)(.*)$").unwrap();
+    static ref ISSUE_NUMBER: Regex = Regex::new(r"^#\d+$").unwrap();
+    static ref ISSUE_TICKET: Regex = Regex::new(r"^[A-Z][A-Z0-9]+-\d+$").unwrap();
+    static ref LEADING_TICKET: Regex =
+        Regex::new(r"^(?:\[[A-Z][A-Z0-9]+-\d+\]|[A-Z][A-Z0-9]+-\d+:?)\s+").unwrap();
+    static ref REVERTS_COMMIT: Regex =
+        Regex::new(r"^This reverts commit ([0-9a-fA-F]{40})\.?$").unwrap();
+    static ref EMPTY_SCOPE_PARENS: Regex = Regex::new(r"^[^(:]*\(\s*\)").unwrap();
+    static ref SEMVER: Regex = Regex::new(
+        r"(?:^|[^\w.])v?\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?(?:$|[^\w.])"
+    )
+    .unwrap();
+    static ref URL: Regex = Regex::new(r"^(?:[a-zA-Z][a-zA-Z0-9+.-]*://|www\.)\S+$").unwrap();
+}
+
+// Scopes can list several segments, like `api,ui` or `api/users`.
+pub(crate) fn scope_segments(scope: &str) -> Vec<&str> {
+    scope
+        .split([',', '/'])
+        .map(|segment| segment.trim())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+// Only the comma separated scopes of a list, `api/users` is a single scope.
+pub(crate) fn scope_list(scope: &str) -> Vec<&str> {
+    scope
+        .split(',')
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+fn duplicate_scopes(scope: &str) -> Vec<Diagnostic> {
+    let list = scope_list(scope);
+    list.iter()
+        .enumerate()
+        .filter(|(index, entry)| {
+            list[..*index].contains(entry) && !list[index + 1..].contains(entry)
+        })
+        .map(|(_, entry)| {
+            Diagnostic::warning(
+                "scope-duplicate",
+                &format!("Scope '{}' is listed more than once", entry),
+            )
+        })
+        .collect()
+}
+
+// Every scope of a list is checked on its own, as the comma only separates
+// the scopes.
+fn scope_separators(scope: &str) -> Vec<Diagnostic> {
+    scope_list(scope)
+        .into_iter()
+        .filter(|entry| entry.contains('/') && entry.contains('.'))
+        .map(|entry| {
+            Diagnostic::warning(
+                "scope-separators",
+                &format!(
+                    "Scope '{}' mixes the separators '/' and '.', use one of them",
+                    entry
+                ),
+            )
+        })
+        .collect()
+}
+
+fn scope_order(scope: &str) -> Option<Diagnostic> {
+    let list = scope_list(scope);
+    let mut sorted = list.clone();
+    sorted.sort();
+    if list != sorted {
+        sorted.dedup();
+        return Some(Diagnostic::warning(
+            "scope-order",
+            &format!("Scopes should be sorted, like '{}'", sorted.join(",")),
+        ));
+    }
+    None
+}
+
+fn scope_case(case: ScopeCase, scope: &str) -> Vec<Diagnostic> {
+    scope_segments(scope)
+        .into_iter()
+        .filter(|segment| match case {
+            ScopeCase::Any => false,
+            ScopeCase::Lower => segment.to_lowercase() != *segment,
+            ScopeCase::Kebab => !KEBAB_CASE.is_match(segment),
+        })
+        .map(|segment| {
+            Diagnostic::error(
+                "scope-case",
+                &format!(
+                    "Scope '{}' has to be {}",
+                    segment,
+                    match case {
+                        ScopeCase::Kebab => "kebab-case, e.g. 'user-profile'",
+                        _ => "lowercase",
+                    }
+                ),
+            )
+        })
+        .collect()
+}
+
+fn subject_case(case: SubjectCase, description: &str) -> Option<Diagnostic> {
+    let first = description.chars().find(|c| c.is_alphabetic())?;
+    let (valid, expected) = match case {
+        SubjectCase::Any => (true, ""),
+        SubjectCase::Lower => (!first.is_uppercase(), "start with a lowercase letter"),
+        SubjectCase::Sentence => (!first.is_lowercase(), "start with an uppercase letter"),
+        SubjectCase::Upper => (
+            !description.chars().any(|c| c.is_lowercase()),
+            "be all uppercase",
+        ),
+    };
+    if valid {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "subject-case",
+        &format!("Description has to {}", expected),
+    ))
+}
+
+fn allowed_verbs(verbs: &[String], description: &str) -> Option<Diagnostic> {
+    let word = description.split_whitespace().next()?;
+    if verbs.iter().any(|verb| verb.eq_ignore_ascii_case(word)) {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "description-verb",
+        &format!(
+            "Description starts with '{}', expected one of: {}",
+            word,
+            verbs.join(", ")
+        ),
+    ))
+}
+
+fn ascii_only_subject(description: &str) -> Option<Diagnostic> {
+    let (position, c) = description
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !c.is_ascii())?;
+    Some(Diagnostic::error(
+        "subject-ascii",
+        &format!(
+            "Description has to be ASCII only, but contains '{}' at position {}",
+            c,
+            position + 1
+        ),
+    ))
+}
+
+// The characters Windows-1252 has in place of the C1 control characters of
+// Latin-1, indexed by their byte minus 0x80.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+// The byte a character stands for when UTF-8 was wrongly read as Latin-1 or
+// Windows-1252.
+fn single_byte(c: char) -> Option<u8> {
+    match WINDOWS_1252.iter().position(|&special| special == c) {
+        Some(index) => Some(0x80 + index as u8),
+        None if ('\u{a0}'..='\u{ff}').contains(&c) => Some(c as u8),
+        None => None,
+    }
+}
+
+// Text that was encoded as UTF-8 twice has runs of characters that are the
+// bytes of a UTF-8 sequence, like `Ã©` for `é`. To avoid false positives, a
+// run is only reported if it decodes to non-ASCII UTF-8 as a whole.
+fn mojibake(description: &str) -> Option<Diagnostic> {
+    let chars: Vec<char> = description.chars().collect();
+    let mut start = 0;
+    while start < chars.len() {
+        let length = chars[start..]
+            .iter()
+            .take_while(|&&c| single_byte(c).is_some())
+            .count();
+        if length < 2 {
+            start += 1;
+            continue;
+        }
+        let run = &chars[start..start + length];
+        let bytes: Vec<u8> = run.iter().filter_map(|&c| single_byte(c)).collect();
+        if let Ok(decoded) = String::from_utf8(bytes) {
+            return Some(Diagnostic::warning(
+                "description-encoding",
+                &format!(
+                    "Description looks like it was encoded twice, '{}' should probably be '{}'",
+                    run.iter().collect::<String>(),
+                    decoded
+                ),
+            ));
+        }
+        start += length;
+    }
+    None
+}
+
+fn release_version(
+    release: &(String, String),
+    ignore_case: bool,
+    commit: &Commit,
+) -> Option<Diagnostic> {
+    let (commit_type, scope) = release;
+    let matches = |expected: &str, actual: &str| match ignore_case {
+        true => expected.eq_ignore_ascii_case(actual),
+        false => expected == actual,
+    };
+    if !matches(commit_type, &commit.commit_type)
+        || (!scope.is_empty() && !matches(scope, &commit.scope))
+        || SEMVER.is_match(&commit.description)
+    {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "release-version",
+        &format!(
+            "Release commit '{}' has to name the version it releases, like 1.2.3 or v1.2.3",
+            commit.description
+        ),
+    ))
+}
+
+// A ticket like `API-123 `, `API-123: ` or `[API-123] ` in front of the
+// description.
+fn without_ticket(description: &str) -> &str {
+    match LEADING_TICKET.find(description) {
+        Some(ticket) => &description[ticket.end()..],
+        None => description,
+    }
+}
+
+fn max_description_length(max: usize, description: &str) -> Option<Diagnostic> {
+    let length = description.chars().count();
+    if length > max {
+        return Some(Diagnostic::error(
+            "max-description-length",
+            &format!(
+                "Description is {} characters long, but at most {} are allowed",
+                length, max
+            ),
+        ));
+    }
+    None
+}
+
+// The first segment of the scope decides which types are allowed, so
+// `docs/readme` is covered by a mapping for `docs`.
+fn scope_type(
+    scope_types: &[(String, Vec<String>)],
+    ignore_case: bool,
+    commit_type: &str,
+    scope: &str,
+) -> Option<Diagnostic> {
+    let prefix = scope.split('/').next()?.trim();
+    let (_, allowed) = scope_types
+        .iter()
+        .find(|(existing, _)| existing == prefix)?;
+    let matches = |allowed: &String| match ignore_case {
+        true => allowed.eq_ignore_ascii_case(commit_type),
+        false => allowed == commit_type,
+    };
+    if allowed.iter().any(matches) {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "scope-type",
+        &format!(
+            "Scope '{}' only allows the types {}, but got '{}'",
+            scope,
+            allowed.join(", "),
+            commit_type
+        ),
+    ))
+}
+
+// The body is trimmed while parsing, so blank lines around it don't count,
+// but the ones between its paragraphs do.
+fn max_body_lines(max: usize, body: &str) -> Option<Diagnostic> {
+    let lines = body.lines().count();
+    if lines > max {
+        return Some(Diagnostic::error(
+            "max-body-lines",
+            &format!("Body has {} lines, but at most {} are allowed", lines, max),
+        ));
+    }
+    None
+}
+
+fn url_only_description(description: &str) -> Option<Diagnostic> {
+    if URL.is_match(description.trim()) {
+        return Some(Diagnostic::warning(
+            "url-only-description",
+            "Description is only a URL, describe the change instead",
+        ));
+    }
+    None
+}
+
+// Only a heuristic, a first word with one of the suffixes that isn't long
+// enough to have a stem, like `red`, or that is an exception passes.
+fn non_imperative(
+    suffixes: &[String],
+    exceptions: &[String],
+    description: &str,
+) -> Option<Diagnostic> {
+    let word = description.split_whitespace().next()?.to_lowercase();
+    if exceptions
+        .iter()
+        .any(|exception| exception.eq_ignore_ascii_case(&word))
+    {
+        return None;
+    }
+    let suffix = suffixes
+        .iter()
+        .find(|suffix| word.len() > suffix.len() + 2 && word.ends_with(&suffix.to_lowercase()))?;
+    Some(Diagnostic::warning(
+        "imperative-mood",
+        &format!(
+            "Description starts with '{}', use the imperative mood instead, without '-{}'",
+            word, suffix
+        ),
+    ))
+}
+
+// Only a heuristic: the first word is the type with one of a few inflections,
+// like `fixes` or `feature`, so `ci: circle` isn't flagged.
+fn redundant_type(commit_type: &str, description: &str) -> Option<Diagnostic> {
+    let word = description.split_whitespace().next()?.to_lowercase();
+    let suffix = word.strip_prefix(&commit_type.to_lowercase())?;
+    if ["", "s", "es", "ed", "ing", "ure", "ures"].contains(&suffix) {
+        return Some(Diagnostic::warning(
+            "redundant-type",
+            &format!(
+                "Description starts with '{}', which repeats the commit type",
+                word
+            ),
+        ));
+    }
+    None
+}
+
+// The description is trimmed while parsing, so this looks at the raw header
+// line. Types and scopes can't contain a colon, so the first one is the
+// separator.
+pub(crate) fn separator_spacing(header: &str) -> Option<Diagnostic> {
+    let (prefix, rest) = header.split_once(':')?;
+    let rest = rest.trim_end();
+    if rest.is_empty() {
+        return None;
+    }
+    let spaces = rest.len() - rest.trim_start().len();
+    let message = match spaces {
+        1 if rest.starts_with(' ') => return None,
+        0 => "Expected a space after the colon, but found none".to_string(),
+        _ => format!(
+            "Expected exactly one space after the colon, but found '{}'",
+            rest[..spaces].escape_debug()
+        ),
+    };
+    let start = prefix.len() + 1;
+    Some(Diagnostic::error("separator-spacing", &message).with_span(start, start + spaces))
+}
+
+// The parser reads `feat(): x` like `feat: x`, so this looks at the raw
+// header line as well.
+pub(crate) fn empty_scope_parens(mode: EmptyScopeParens, header: &str) -> Option<Diagnostic> {
+    let indent = header.len() - header.trim_start().len();
+    let parens = EMPTY_SCOPE_PARENS.find(header.trim_start())?;
+    let start = indent + parens.as_str().rfind('(').unwrap_or(0);
+    let message = "The header has empty parentheses instead of a scope, remove them";
+    let diagnostic = match mode {
+        EmptyScopeParens::Error => Diagnostic::error("scope-empty", message),
+        EmptyScopeParens::Warn => Diagnostic::warning("scope-empty", message),
+        EmptyScopeParens::Accept => return None,
+    };
+    Some(diagnostic.with_span(start, indent + parens.end()))
+}
+
+fn branch_ticket(ticket: &str, commit: &Commit) -> Option<Diagnostic> {
+    let referenced = commit.description.contains(ticket)
+        || commit.scope.contains(ticket)
+        || commit.body.contains(ticket);
+    if referenced {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "branch-ticket",
+        &format!(
+            "The commit doesn't reference the ticket '{}' of the branch",
+            ticket
+        ),
+    ))
+}
+
+fn trailing_issue_ref(description: &str) -> Option<Diagnostic> {
+    let captures = TRAILING_ISSUE_REF.captures(description.trim_end())?;
+    let reference = &captures[1];
+    Some(Diagnostic::warning(
+        "trailing-issue-ref",
+        &format!(
+            "Description ends with the issue reference '{}', consider moving it into a footer like 'Closes: {}'",
+            reference, reference
+        ),
+    ))
+}
+
+// Counts the bytes of the raw message, as that's what systems limiting the
+// size of a commit message store.
+pub(crate) fn max_message_bytes(max: usize, message: &str) -> Option<Diagnostic> {
+    if message.len() > max {
+        return Some(Diagnostic::error(
+            "max-message-bytes",
+            &format!(
+                "Message is {} bytes long, but at most {} are allowed",
+                message.len(),
+                max
+            ),
+        ));
+    }
+    None
+}
+
+// Checks the message as it is given, as the body of a commit is trimmed.
+// The blank line that separates the body from the header doesn't count, and
+// neither do blank lines at the end, which git strips.
+pub(crate) fn consecutive_blank_lines(max: usize, message: &str) -> Option<Diagnostic> {
+    let mut lines: Vec<&str> = message.lines().skip(1).collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    if lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    let mut longest = 0;
+    let mut current = 0;
+    for line in lines {
+        current = match line.trim().is_empty() {
+            true => current + 1,
+            false => 0,
+        };
+        longest = longest.max(current);
+    }
+    if longest > max {
+        return Some(Diagnostic::warning(
+            "body-blank-lines",
+            &format!(
+                "Body has {} blank lines in a row, but at most {} are allowed",
+                longest, max
+            ),
+        ));
+    }
+    None
+}
+
+fn forbidden_body_words(words: &[String], body: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for (index, line) in body.lines().enumerate() {
+        let line = line.to_lowercase();
+        for word in words {
+            if line.contains(&word.to_lowercase()) {
+                diagnostics.push(Diagnostic::error(
+                    "body-forbidden-words",
+                    &format!(
+                        "Line {} of the body contains the forbidden word '{}'",
+                        index + 1,
+                        word
+                    ),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+// A section starts with a Markdown heading like `## Why`, or with a line
+// like `Why:`, both case insensitive.
+fn body_sections(sections: &[String], body: &str) -> Vec<Diagnostic> {
+    let headings: Vec<String> = body
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            match line.strip_prefix('#') {
+                Some(heading) => Some(heading.trim_start_matches('#').trim()),
+                None => line.split_once(':').map(|(heading, _)| heading.trim()),
+            }
+        })
+        .map(|heading| heading.to_lowercase())
+        .collect();
+    sections
+        .iter()
+        .filter(|section| !headings.contains(&section.to_lowercase()))
+        .map(|section| {
+            Diagnostic::error(
+                "body-sections",
+                &format!(
+                    "Body is missing the section '{}', add it as '## {}' or '{}:'",
+                    section, section, section
+                ),
+            )
+        })
+        .collect()
+}
+
+// A trailing newline after the header doesn't count as a body, only lines
+// after it.
+pub(crate) fn empty_body(message: &str) -> Option<Diagnostic> {
+    let mut lines = message.lines().skip(1).peekable();
+    lines.peek()?;
+    if lines.all(|line| line.trim().is_empty()) {
+        return Some(Diagnostic::warning(
+            "body-empty",
+            "Body present but empty, it only consists of whitespace",
+        ));
+    }
+    None
+}
+
+// Compares case insensitively and ignores punctuation at the end and extra
+// whitespace, so `Add the endpoint.` repeats `add the endpoint`.
+fn body_duplicates_subject(description: &str, body: &str) -> Option<Diagnostic> {
+    let normalize = |text: &str| {
+        text.trim_end_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    let first_line = body.lines().find(|line| !line.trim().is_empty())?;
+    let description = normalize(description);
+    if !description.is_empty() && normalize(first_line) == description {
+        return Some(Diagnostic::warning(
+            "body-duplicates-subject",
+            "The first line of the body repeats the description, explain the change instead",
+        ));
+    }
+    None
+}
+
+// The line `git revert` writes into the body, like
+// `This reverts commit 1f0e3f4b....`, with the full hash of the commit.
+fn revert_sha(commit_type: &str, body: &str) -> Option<Diagnostic> {
+    if !commit_type.eq_ignore_ascii_case("revert") {
+        return None;
+    }
+    let line = body
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| line.starts_with("This reverts commit"));
+    match line {
+        Some(line) if REVERTS_COMMIT.is_match(line) => None,
+        Some(line) => Some(Diagnostic::warning(
+            "revert-sha",
+            &format!(
+                "'{}' should name the full 40 character hash of the reverted commit",
+                line
+            ),
+        )),
+        None => Some(Diagnostic::warning(
+            "revert-sha",
+            "Revert commits should name the reverted commit with 'This reverts commit <sha>.' in the body",
+        )),
+    }
+}
+
+// Any segment of a scope like `api,ui` counts, case insensitively.
+fn body_mentions_scope(scope: &str, body: &str) -> Option<Diagnostic> {
+    let segments = scope_segments(scope);
+    if segments.is_empty() {
+        return None;
+    }
+    let body = body.to_lowercase();
+    if segments
+        .iter()
+        .any(|segment| body.contains(&segment.to_lowercase()))
+    {
+        return None;
+    }
+    Some(Diagnostic::warning(
+        "body-mentions-scope",
+        &format!("Body doesn't mention the scope '{}'", scope),
+    ))
+}
+
+fn trailing_period(description: &str) -> Option<Diagnostic> {
+    if description.ends_with('.') {
+        return Some(Diagnostic::warning(
+            "trailing-period",
+            "Description should not end with a period",
+        ));
+    }
+    None
+}
+
+fn trailing_ellipsis(description: &str) -> Option<Diagnostic> {
+    if description.ends_with("...") || description.ends_with('…') {
+        return Some(Diagnostic::warning(
+            "description-ellipsis",
+            "Description ends with an ellipsis, is it unfinished?",
+        ));
+    }
+    None
+}
+
+// A footer without text, like `BREAKING CHANGE:`, doesn't even parse as a
+// footer once the trailing space is trimmed, so this looks at the lines.
+fn breaking_footer_text(body: &str) -> Vec<Diagnostic> {
+    body.lines()
+        .map(|line| line.trim())
+        .filter(|line| {
+            line.strip_suffix(':')
+                .or_else(|| line.strip_suffix(" #"))
+                .is_some_and(|token| is_breaking_token(token.trim_end()))
+        })
+        .map(|line| {
+            Diagnostic::error(
+                "breaking-change-empty",
+                &format!("Footer '{}' has to describe the breaking change", line),
+            )
+        })
+        .collect()
+}
+
+fn footer_token_case(footers: &[(String, String)]) -> Vec<Diagnostic> {
+    footers
+        .iter()
+        .filter(|(token, _)| !is_breaking_token(token))
+        .filter(|(token, _)| token.starts_with(|c: char| c.is_ascii_lowercase()))
+        .map(|(token, _)| {
+            let mut canonical = token.to_string();
+            canonical[..1].make_ascii_uppercase();
+            Diagnostic::warning(
+                "footer-token-case",
+                &format!(
+                    "Footer token '{}' should be capitalized as '{}'",
+                    token, canonical
+                ),
+            )
+        })
+        .collect()
+}
+
+// Trailer tokens are case insensitive in git, and both spellings of a
+// breaking change are allowed once one of them is.
+fn allowed_footers(allowed: &[String], footers: &[(String, String)]) -> Vec<Diagnostic> {
+    footers
+        .iter()
+        .filter(|(token, _)| {
+            !allowed.iter().any(|allowed| {
+                allowed.eq_ignore_ascii_case(token)
+                    || (is_breaking_token(allowed) && is_breaking_token(token))
+            })
+        })
+        .map(|(token, _)| {
+            Diagnostic::error(
+                "footer-allowed",
+                &format!(
+                    "Footer token '{}' is not allowed, expected one of: {}",
+                    token,
+                    allowed.join(", ")
+                ),
+            )
+        })
+        .collect()
+}
+
+// The parsed footers don't keep the separator, `Closes #12` and `Closes: #12`
+// are both `#12`, so this looks at the lines of the footers.
+fn issue_footer_style(style: IssueFooterStyle, body: &str) -> Vec<Diagnostic> {
+    if footer::parse(body).is_empty() {
+        return vec![];
+    }
+    let paragraph = body.trim_end().rsplit("\n\n").next().unwrap_or("");
+    paragraph
+        .lines()
+        .filter_map(|line| ISSUE_FOOTER.captures(line))
+        .filter(|captures| {
+            let (separator, value) = (&captures[2], captures[3].trim());
+            !match style {
+                IssueFooterStyle::Hash => {
+                    separator == " #" && ISSUE_NUMBER.is_match(&format!("#{}", value))
+                }
+                IssueFooterStyle::ColonHash => separator == ": " && ISSUE_NUMBER.is_match(value),
+                IssueFooterStyle::Ticket => separator == ": " && ISSUE_TICKET.is_match(value),
+            }
+        })
+        .map(|captures| {
+            Diagnostic::error(
+                "issue-footer-style",
+                &format!(
+                    "Footer '{}' has to be written like '{}'",
+                    &captures[0],
+                    match style {
+                        IssueFooterStyle::Hash => format!("{} #12", &captures[1]),
+                        IssueFooterStyle::ColonHash => format!("{}: #12", &captures[1]),
+                        IssueFooterStyle::Ticket => format!("{}: JIRA-3", &captures[1]),
+                    }
+                ),
+            )
+        })
+        .collect()
+}
+
+// Footers glued to the body text aren't footers for git, as the last
+// paragraph has to consist of footers only. So this looks for footer lines
+// at the end of a paragraph that starts with ordinary text.
+fn footer_blank_line(body: &str) -> Option<Diagnostic> {
+    let paragraph = body.trim_end().rsplit("\n\n").next()?;
+    let lines: Vec<&str> = paragraph.lines().collect();
+    let footers = lines
+        .iter()
+        .rev()
+        .take_while(|line| footer::parse_line(line).is_some())
+        .count();
+    if footers == 0 || footers == lines.len() {
+        return None;
+    }
+    Some(Diagnostic::warning(
+        "footer-blank-line",
+        &format!(
+            "Footer '{}' has to be separated from the body by a blank line",
+            lines[lines.len() - footers]
+        ),
+    ))
+}
+
+fn co_author_email(footers: &[(String, String)]) -> Vec<Diagnostic> {
+    footers
+        .iter()
+        .filter(|(token, _)| token.eq_ignore_ascii_case("Co-authored-by"))
+        .filter(|(_, value)| !CO_AUTHOR.is_match(value.trim()))
+        .map(|(token, value)| {
+            Diagnostic::warning(
+                "co-author-email",
+                &format!(
+                    "Footer '{}: {}' should be in the format 'Name <email>'",
+                    token, value
+                ),
+            )
+        })
+        .collect()
+}
+
+// A reviewer is known if the allowlist has the whole value, like
+// `Jane Doe <jane@example.com>`, or just the name or the email of it.
+fn reviewers(allowed: &[String], footers: &[(String, String)]) -> Vec<Diagnostic> {
+    footers
+        .iter()
+        .filter(|(token, _)| {
+            token.eq_ignore_ascii_case("Reviewed-by") || token.eq_ignore_ascii_case("Acked-by")
+        })
+        .filter(|(_, value)| {
+            let value = value.trim();
+            let (name, email) = match value.split_once('<') {
+                Some((name, email)) => (name.trim(), email.trim_end_matches('>').trim()),
+                None => (value, ""),
+            };
+            !allowed.iter().any(|reviewer| {
+                [value, name, email]
+                    .iter()
+                    .any(|part| !part.is_empty() && reviewer.eq_ignore_ascii_case(part))
+            })
+        })
+        .map(|(token, value)| {
+            Diagnostic::error(
+                "footer-reviewer",
+                &format!(
+                    "Footer '{}: {}' names a reviewer that is not in the list of reviewers",
+                    token, value
+                ),
+            )
+        })
+        .collect()
+}
+
+// The prefix is the header up to the colon, without the `!` of a breaking
+// change.
+pub(crate) fn prefix_pattern(
+    pattern: &Pattern,
+    commit_type: &str,
+    scope: &str,
+) -> Option<Diagnostic> {
+    let prefix = match scope {
+        "" => commit_type.to_string(),
+        scope => format!("{}({})", commit_type, scope),
+    };
+    if pattern.0.is_match(&prefix) {
+        return None;
+    }
+    Some(Diagnostic::error(
+        "prefix-pattern",
+        &format!(
+            "Prefix '{}' does not match the pattern '{}'",
+            prefix,
+            pattern.0.as_str()
+        ),
+    ))
+}
+
+fn description_pattern(pattern: &Pattern, description: &str) -> Option<Diagnostic> {
+    if !pattern.0.is_match(description) {
+        return Some(Diagnostic::error(
+            "description-pattern",
+            &format!(
+                "Description '{}' does not match the pattern '{}'",
+                description,
+                pattern.0.as_str()
+            ),
+        ));
+    }
+    None
+}
+
+// The ids of the rules `check` runs for the config, in the same order.
+pub(crate) fn checked(config: &Config) -> Vec<&'static str> {
+    let optional = [
+        ("description-ellipsis", config.warn_trailing_ellipsis),
+        ("trailing-period", true),
+        (
+            "scope-case",
+            config.scope_case != ScopeCase::Any || !config.lowercase_scope_for.is_empty(),
+        ),
+        ("scope-duplicate", true),
+        ("body-duplicates-subject", true),
+        ("scope-order", config.sort_scopes),
+        ("scope-separators", config.warn_mixed_scope_separators),
+        ("scope-type", !config.scope_types.is_empty()),
+        ("subject-case", config.subject_case != SubjectCase::Any),
+        ("description-verb", !config.allowed_verbs.is_empty()),
+        ("subject-ascii", config.ascii_only_subject),
+        ("description-encoding", config.encoding_check),
+        (
+            "max-description-length",
+            config.max_description_length.is_some(),
+        ),
+        ("max-body-lines", config.max_body_lines.is_some()),
+        (
+            "body-forbidden-words",
+            !config.forbidden_body_words.is_empty(),
+        ),
+        ("body-sections", !config.required_body_sections.is_empty()),
+        ("branch-ticket", config.branch_ticket.is_some()),
+        ("trailing-issue-ref", config.warn_trailing_issue_ref),
+        ("revert-sha", config.require_revert_sha),
+        ("release-version", config.require_release_version),
+        ("body-mentions-scope", config.body_mentions_scope),
+        ("imperative-mood", config.warn_non_imperative),
+        ("redundant-type", config.warn_redundant_type),
+        ("url-only-description", config.warn_url_only_description),
+        ("breaking-change-empty", true),
+        ("co-author-email", true),
+        ("footer-reviewer", !config.reviewers.is_empty()),
+        ("footer-allowed", !config.allowed_footers.is_empty()),
+        ("issue-footer-style", config.issue_footer_style.is_some()),
+        ("footer-blank-line", config.footer_blank_line),
+        ("footer-token-case", config.capitalized_footer_tokens),
+        ("description-pattern", config.description_pattern.is_some()),
+    ];
+    optional
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(rule, _)| rule)
+        .collect()
+}
+
+pub(crate) fn check(config: &Config, commit: &Commit) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    // An ellipsis is reported on its own, not as a trailing period as well.
+    match config.warn_trailing_ellipsis {
+        true => diagnostics.extend(
+            trailing_ellipsis(&commit.description).or_else(|| trailing_period(&commit.description)),
+        ),
+        false => diagnostics.extend(trailing_period(&commit.description)),
+    }
+    diagnostics.extend(scope_case(config.scope_case, &commit.scope));
+    // A global scope case already covers the types of the list.
+    if config.scope_case == ScopeCase::Any
+        && config.lowercase_scope_for.iter().any(|name| {
+            name == &commit.commit_type
+                || (config.ignore_case && name.eq_ignore_ascii_case(&commit.commit_type))
+        })
+    {
+        diagnostics.extend(scope_case(ScopeCase::Lower, &commit.scope));
+    }
+    diagnostics.extend(duplicate_scopes(&commit.scope));
+    diagnostics.extend(body_duplicates_subject(&commit.description, &commit.body));
+    if config.sort_scopes {
+        diagnostics.extend(scope_order(&commit.scope));
+    }
+    if config.warn_mixed_scope_separators {
+        diagnostics.extend(scope_separators(&commit.scope));
+    }
+    diagnostics.extend(scope_type(
+        &config.scope_types,
+        config.ignore_case,
+        &commit.commit_type,
+        &commit.scope,
+    ));
+    diagnostics.extend(subject_case(config.subject_case, &commit.description));
+    if !config.allowed_verbs.is_empty() {
+        diagnostics.extend(allowed_verbs(&config.allowed_verbs, &commit.description));
+    }
+    if config.ascii_only_subject {
+        diagnostics.extend(ascii_only_subject(&commit.description));
+    }
+    if config.encoding_check {
+        diagnostics.extend(mojibake(&commit.description));
+    }
+    if let Some(max) = config.max_description_length {
+        let description = match config.length_excludes_ticket {
+            true => without_ticket(&commit.description),
+            false => &commit.description,
+        };
+        diagnostics.extend(max_description_length(max, description));
+    }
+    if let Some(max) = config.max_body_lines {
+        diagnostics.extend(max_body_lines(max, &commit.body));
+    }
+    diagnostics.extend(forbidden_body_words(
+        &config.forbidden_body_words,
+        &commit.body,
+    ));
+    diagnostics.extend(body_sections(&config.required_body_sections, &commit.body));
+    if let Some(ticket) = &config.branch_ticket {
+        diagnostics.extend(branch_ticket(ticket, commit));
+    }
+    if config.warn_trailing_issue_ref {
+        diagnostics.extend(trailing_issue_ref(&commit.description));
+    }
+    if config.require_revert_sha {
+        diagnostics.extend(revert_sha(&commit.commit_type, &commit.body));
+    }
+    if config.require_release_version {
+        diagnostics.extend(release_version(
+            &config.release_commit,
+            config.ignore_case,
+            commit,
+        ));
+    }
+    if config.body_mentions_scope {
+        diagnostics.extend(body_mentions_scope(&commit.scope, &commit.body));
+    }
+    if config.warn_non_imperative {
+        diagnostics.extend(non_imperative(
+            &config.non_imperative_suffixes,
+            &config.imperative_exceptions,
+            &commit.description,
+        ));
+    }
+    if config.warn_redundant_type {
+        diagnostics.extend(redundant_type(&commit.commit_type, &commit.description));
+    }
+    if config.warn_url_only_description {
+        diagnostics.extend(url_only_description(&commit.description));
+    }
+    diagnostics.extend(breaking_footer_text(&commit.body));
+    diagnostics.extend(co_author_email(&commit.footers));
+    if !config.reviewers.is_empty() {
+        diagnostics.extend(reviewers(&config.reviewers, &commit.footers));
+    }
+    if !config.allowed_footers.is_empty() {
+        diagnostics.extend(allowed_footers(&config.allowed_footers, &commit.footers));
+    }
+    if let Some(style) = config.issue_footer_style {
+        diagnostics.extend(issue_footer_style(style, &commit.body));
+    }
+    if config.footer_blank_line {
+        diagnostics.extend(footer_blank_line(&commit.body));
+    }
+    if config.capitalized_footer_tokens {
+        diagnostics.extend(footer_token_case(&commit.footers));
+    }
+    if let Some(pattern) = &config.description_pattern {
+        diagnostics.extend(description_pattern(pattern, &commit.description));
+    }
+    diagnostics
+}
+
+#[test]
+fn test_scope_separators() {
+    for scope in [
+        "api/users/auth",
+        "api.users",
+        "api/users,core.auth",
+        "",
+        "api",
+    ] {
+        assert!(scope_separators(scope).is_empty(), "{}", scope);
+    }
+    let diagnostics = scope_separators("api/users.auth,ui");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Scope 'api/users.auth' mixes the separators '/' and '.', use one of them"
+    );
+    assert_eq!(diagnostics[0].code, "scope-separators");
+}
+
+#[test]
+fn test_trailing_period() {
+    assert!(trailing_period("add a feature").is_none());
+    assert_eq!(
+        trailing_period("add a feature.").unwrap().code,
+        "trailing-period"
+    );
+}
+
+#[test]
+fn test_empty_scope_parens() {
+    for header in ["feat(): x", "feat( )!: x", " fix(): x"] {
+        let diagnostic = empty_scope_parens(EmptyScopeParens::Error, header).unwrap();
+        assert_eq!(diagnostic.code, "scope-empty");
+        assert_eq!(diagnostic.severity, crate::diagnostic::Severity::Error);
+        assert_eq!(
+            empty_scope_parens(EmptyScopeParens::Warn, header)
+                .unwrap()
+                .severity,
+            crate::diagnostic::Severity::Warning
+        );
+        assert!(empty_scope_parens(EmptyScopeParens::Accept, header).is_none());
+    }
+    for header in ["feat: x", "feat(api): x", "feat: call f()"] {
+        assert!(empty_scope_parens(EmptyScopeParens::Error, header).is_none());
+    }
+}
+
+#[test]
+fn test_trailing_ellipsis() {
+    assert!(trailing_ellipsis("add a feature").is_none());
+    assert!(trailing_ellipsis("add a feature.").is_none());
+    assert!(trailing_ellipsis("add ... to the menu").is_none());
+    for description in ["add a feature...", "add a feature…"] {
+        assert_eq!(
+            trailing_ellipsis(description).unwrap().code,
+            "description-ellipsis"
+        );
+    }
+}
+
+#[test]
+fn test_breaking_footer_text() {
+    assert!(breaking_footer_text("Body\n\nBREAKING CHANGE: v1 is gone").is_empty());
+    assert!(breaking_footer_text("").is_empty());
+    for body in ["Body\n\nBREAKING CHANGE:", "BREAKING-CHANGE:\nRefs: #1"] {
+        let diagnostics = breaking_footer_text(body);
+        assert_eq!(
+            diagnostics.len(),
+            1,
+            "Unexpected diagnostics for '{}'",
+            body
+        );
+        assert_eq!(diagnostics[0].code, "breaking-change-empty");
+    }
+    assert_eq!(
+        breaking_footer_text("BREAKING CHANGE:")[0].message,
+        "Footer 'BREAKING CHANGE:' has to describe the breaking change"
+    );
+}
+
+#[test]
+fn test_footer_token_case() {
+    let footers = crate::footer::parse("signed-off-by: Jane Doe <jane@example.com>");
+    let diagnostics = footer_token_case(&footers);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "footer-token-case");
+    assert!(diagnostics[0].message.contains("'Signed-off-by'"));
+
+    let footers =
+        crate::footer::parse("Signed-off-by: Jane Doe <jane@example.com>\nBREAKING CHANGE: gone");
+    assert!(footer_token_case(&footers).is_empty());
+}
+
+#[test]
+fn test_description_pattern() {
+    let pattern = Pattern::new(r"^(?:.*\(#\d+\))$").unwrap();
+    assert!(description_pattern(&pattern, "add login (#123)").is_none());
+    assert_eq!(
+        description_pattern(&pattern, "add login").unwrap().code,
+        "description-pattern"
+    );
+    assert!(description_pattern(&pattern, "add login (#123) later").is_some());
+}
+
+#[test]
+fn test_scope_case() {
+    let test_cases = vec![
+        (ScopeCase::Any, "UserProfile", 0),
+        (ScopeCase::Lower, "UserProfile", 1),
+        (ScopeCase::Lower, "user profile", 0),
+        (ScopeCase::Kebab, "UserProfile", 1),
+        (ScopeCase::Kebab, "user profile", 1),
+        (ScopeCase::Kebab, "user-profile", 0),
+        (ScopeCase::Kebab, "-user", 1),
+        (ScopeCase::Kebab, "user-", 1),
+        (ScopeCase::Kebab, "api,user-profile", 0),
+        (ScopeCase::Kebab, "api/Users,UI", 2),
+    ];
+    for (case, scope, expected) in test_cases {
+        assert_eq!(
+            scope_case(case, scope).len(),
+            expected,
+            "Unexpected result for '{}' with {:?}",
+            scope,
+            case
+        );
+    }
+}
+
+#[test]
+fn test_subject_case() {
+    let test_cases = vec![
+        (SubjectCase::Any, "Add a feature", true),
+        (SubjectCase::Lower, "add a feature", true),
+        (SubjectCase::Lower, "Add a feature", false),
+        (SubjectCase::Sentence, "Add a feature", true),
+        (SubjectCase::Sentence, "add a feature", false),
+        (SubjectCase::Upper, "ADD A FEATURE", true),
+        (SubjectCase::Upper, "ADD a FEATURE", false),
+        (SubjectCase::Lower, "", true),
+    ];
+    for (case, description, expected) in test_cases {
+        assert_eq!(
+            subject_case(case, description).is_none(),
+            expected,
+            "Unexpected result for '{}' with {:?}",
+            description,
+            case
+        );
+    }
+}
+
+#[test]
+fn test_without_ticket() {
+    assert_eq!(without_ticket("API-123 add login"), "add login");
+    assert_eq!(without_ticket("API-123: add login"), "add login");
+    assert_eq!(without_ticket("[WEB2-7] add login"), "add login");
+    assert_eq!(without_ticket("add API-123 login"), "add API-123 login");
+    assert_eq!(without_ticket("API-123"), "API-123");
+    assert_eq!(without_ticket("api-123 add login"), "api-123 add login");
+}
+
+#[test]
+fn test_max_description_length() {
+    assert!(max_description_length(5, "abcde").is_none());
+    assert!(max_description_length(5, "äöüéè").is_none());
+    assert_eq!(
+        max_description_length(5, "abcdef").unwrap().code,
+        "max-description-length"
+    );
+}
+
+#[test]
+fn test_max_body_lines() {
+    assert!(max_body_lines(3, "").is_none());
+    assert!(max_body_lines(3, "one\n\nthree").is_none());
+    assert_eq!(
+        max_body_lines(3, "one\n\nthree\nfour").unwrap().message,
+        "Body has 4 lines, but at most 3 are allowed"
+    );
+    assert!(max_body_lines(0, "one").is_some());
+}
+
+#[test]
+fn test_url_only_description() {
+    for description in [
+        "https://github.com/MatthiasKainer/rcop/issues/1",
+        "http://example.com",
+        " www.example.com/page ",
+    ] {
+        assert_eq!(
+            url_only_description(description).unwrap().code,
+            "url-only-description",
+            "Should have warned for '{}'",
+            description
+        );
+    }
+    for description in [
+        "fix the link to https://example.com",
+        "https://example.com is unreachable",
+        "handle the www prefix",
+        "",
+    ] {
+        assert!(
+            url_only_description(description).is_none(),
+            "Should not have warned for '{}'",
+            description
+        );
+    }
+}
+
+#[test]
+fn test_duplicate_scopes() {
+    assert!(duplicate_scopes("api,core").is_empty());
+    assert!(duplicate_scopes("api/users").is_empty());
+    assert!(duplicate_scopes("").is_empty());
+    let diagnostics = duplicate_scopes("api, core,api,api");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "scope-duplicate");
+    assert_eq!(
+        diagnostics[0].message,
+        "Scope 'api' is listed more than once"
+    );
+}
+
+#[test]
+fn test_scope_order() {
+    for scope in ["api,core", "api, core", "core", "web/api", ""] {
+        assert!(
+            scope_order(scope).is_none(),
+            "Should not have warned for '{}'",
+            scope
+        );
+    }
+    assert_eq!(
+        scope_order("core,api").unwrap().message,
+        "Scopes should be sorted, like 'api,core'"
+    );
+    assert_eq!(
+        scope_order("core,api,core").unwrap().message,
+        "Scopes should be sorted, like 'api,core'"
+    );
+}
+
+#[test]
+fn test_consecutive_blank_lines() {
+    for message in [
+        "feat: x",
+        "feat: x\n\nfirst\n\nsecond\n",
+        "feat: x\n\nfirst\n\n\n\n",
+    ] {
+        assert!(consecutive_blank_lines(1, message).is_none(), "{}", message);
+    }
+    assert_eq!(
+        consecutive_blank_lines(1, "feat: x\n\nfirst\n\n\n\nsecond")
+            .unwrap()
+            .message,
+        "Body has 3 blank lines in a row, but at most 1 are allowed"
+    );
+    assert_eq!(
+        consecutive_blank_lines(1, "feat: x\n\n \n\t\nfirst")
+            .unwrap()
+            .code,
+        "body-blank-lines"
+    );
+    assert!(consecutive_blank_lines(3, "feat: x\n\nfirst\n\n\n\nsecond").is_none());
+}
+
+#[test]
+fn test_release_version() {
+    let release = ("chore".to_string(), "release".to_string());
+    let commit = |commit_type: &str, scope: &str, description: &str| Commit {
+        commit_type: commit_type.to_string(),
+        scope: scope.to_string(),
+        description: description.to_string(),
+        ..Commit::default()
+    };
+    for description in [
+        "v1.2.3",
+        "release 1.2.3",
+        "1.0.0-rc.1",
+        "publish v2.0.0+build.5",
+    ] {
+        assert!(
+            release_version(&release, false, &commit("chore", "release", description)).is_none(),
+            "{}",
+            description
+        );
+    }
+    for description in ["bump", "v1.2", "release 1.2.3.4", "bump from a1.2.3"] {
+        assert_eq!(
+            release_version(&release, false, &commit("chore", "release", description))
+                .unwrap()
+                .code,
+            "release-version",
+            "{}",
+            description
+        );
+    }
+    assert!(release_version(&release, false, &commit("chore", "deps", "bump")).is_none());
+    assert!(release_version(&release, false, &commit("Chore", "release", "bump")).is_none());
+    assert!(release_version(&release, true, &commit("Chore", "release", "bump")).is_some());
+    let any_scope = ("release".to_string(), "".to_string());
+    assert!(release_version(&any_scope, false, &commit("release", "api", "bump")).is_some());
+}
+
+#[test]
+fn test_mojibake() {
+    for description in [
+        "add the café menu",
+        "support Ärger, Öl and Übel",
+        "ship it 🚀",
+        "add the “quoted” ©",
+        "",
+    ] {
+        assert!(mojibake(description).is_none(), "{}", description);
+    }
+    assert_eq!(
+        mojibake("add the cafÃ© menu").unwrap().message,
+        "Description looks like it was encoded twice, 'Ã©' should probably be 'é'"
+    );
+    assert_eq!(
+        mojibake("fix the â€œquotesâ€\u{9d}").unwrap().message,
+        "Description looks like it was encoded twice, 'â€œ' should probably be '“'"
+    );
+}
+
+#[test]
+fn test_ascii_only_subject() {
+    assert!(ascii_only_subject("add the cafe menu").is_none());
+    assert!(ascii_only_subject("").is_none());
+    assert_eq!(
+        ascii_only_subject("add the café menu").unwrap().message,
+        "Description has to be ASCII only, but contains 'é' at position 12"
+    );
+    assert_eq!(
+        ascii_only_subject("ship it 🚀").unwrap().code,
+        "subject-ascii"
+    );
+}
+
+#[test]
+fn test_allowed_verbs() {
+    let verbs = vec!["add".to_string(), "fix".to_string(), "remove".to_string()];
+    assert!(allowed_verbs(&verbs, "add the endpoint").is_none());
+    assert!(allowed_verbs(&verbs, "Remove the endpoint").is_none());
+    assert!(allowed_verbs(&verbs, "").is_none());
+    assert_eq!(
+        allowed_verbs(&verbs, "added the endpoint").unwrap().message,
+        "Description starts with 'added', expected one of: add, fix, remove"
+    );
+}
+
+#[test]
+fn test_non_imperative() {
+    let list = |items: &[&str]| {
+        items
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>()
+    };
+    let config = Config::default();
+    let check = |suffixes: &[String], exceptions: &[String], description: &str| {
+        non_imperative(suffixes, exceptions, description).is_some()
+    };
+    let (suffixes, exceptions) = (
+        &config.non_imperative_suffixes,
+        &config.imperative_exceptions,
+    );
+    assert!(check(suffixes, exceptions, "added the endpoint"));
+    assert!(check(suffixes, exceptions, "Adding the endpoint"));
+    assert!(check(suffixes, exceptions, "adds the endpoint"));
+    assert!(!check(suffixes, exceptions, "add the endpoint"));
+    assert!(!check(suffixes, exceptions, "process the queue"));
+    assert!(!check(suffixes, exceptions, "red button"));
+    assert_eq!(
+        non_imperative(suffixes, exceptions, "added it")
+            .unwrap()
+            .message,
+        "Description starts with 'added', use the imperative mood instead, without '-ed'"
+    );
+
+    // Custom lists change which words are flagged.
+    let suffixes = list(&["ed", "te"]);
+    assert!(check(&suffixes, &[], "fixed it"));
+    assert!(!check(&suffixes, &[], "adds it"));
+    assert!(check(&suffixes, &[], "update it"));
+    assert!(!check(&suffixes, &list(&["update"]), "update it"));
+    assert!(!check(&list(&["t"]), &[], "fix it"));
+}
+
+#[test]
+fn test_redundant_type() {
+    for (commit_type, description) in [
+        ("fix", "fix login"),
+        ("fix", "Fixes the login"),
+        ("feat", "feature to add"),
+        ("refactor", "refactoring the parser"),
+    ] {
+        assert_eq!(
+            redundant_type(commit_type, description).unwrap().code,
+            "redundant-type",
+            "Should have warned for '{}: {}'",
+            commit_type,
+            description
+        );
+    }
+    for (commit_type, description) in [
+        ("fix", "resolve login"),
+        ("feat", "add feature"),
+        ("ci", "circle config"),
+        ("fix", "fixture for the login"),
+        ("docs", ""),
+    ] {
+        assert!(
+            redundant_type(commit_type, description).is_none(),
+            "Should not have warned for '{}: {}'",
+            commit_type,
+            description
+        );
+    }
+}
+
+#[test]
+fn test_separator_spacing() {
+    for header in [
+        "feat: x",
+        "feat(api): x",
+        "feat!: x",
+        "feat:",
+        "feat: x ",
+        "feat: a: b",
+    ] {
+        assert!(
+            separator_spacing(header).is_none(),
+            "Should not have failed for '{}'",
+            header
+        );
+    }
+    for header in ["feat:x", "feat:  x", "feat(api):\tx", "feat: \tx"] {
+        assert_eq!(
+            separator_spacing(header).map(|d| d.code),
+            Some("separator-spacing"),
+            "Should have failed for '{}'",
+            header
+        );
+    }
+}
+
+#[test]
+fn test_branch_ticket() {
+    let commit = |scope: &str, description: &str, body: &str| Commit {
+        commit_type: "feat".to_string(),
+        scope: scope.to_string(),
+        description: description.to_string(),
+        body: body.to_string(),
+        ..Commit::default()
+    };
+    assert!(branch_ticket("API-123", &commit("api", "API-123 add endpoint", "")).is_none());
+    assert!(branch_ticket("API-123", &commit("API-123", "add endpoint", "")).is_none());
+    assert!(branch_ticket("API-123", &commit("api", "add endpoint", "Refs: API-123")).is_none());
+    assert_eq!(
+        branch_ticket("API-123", &commit("api", "add endpoint", "Refs: API-124"))
+            .unwrap()
+            .code,
+        "branch-ticket"
+    );
+}
+
+#[test]
+fn test_allowed_footers() {
+    let allowed: Vec<String> = ["Refs", "Co-authored-by", "BREAKING CHANGE"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let footers = crate::footer::parse(
+        "Refs: #1\nco-authored-by: Jane <jane@example.com>\nBREAKING-CHANGE: gone\nRandom-Token: x",
+    );
+    let diagnostics = allowed_footers(&allowed, &footers);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "footer-allowed");
+    assert_eq!(
+        diagnostics[0].message,
+        "Footer token 'Random-Token' is not allowed, expected one of: Refs, Co-authored-by, BREAKING CHANGE"
+    );
+}
+
+#[test]
+fn test_trailing_issue_ref() {
+    for description in ["resolve bug #123", "resolve bug (#123)", "#123"] {
+        assert_eq!(
+            trailing_issue_ref(description).unwrap().code,
+            "trailing-issue-ref",
+            "Should have warned for '{}'",
+            description
+        );
+    }
+    for description in [
+        "resolve #123 in the parser",
+        "resolve bug#123",
+        "update to v#2a",
+        "",
+    ] {
+        assert!(
+            trailing_issue_ref(description).is_none(),
+            "Should not have warned for '{}'",
+            description
+        );
+    }
+}
+
+#[test]
+fn test_max_message_bytes() {
+    assert!(max_message_bytes(10, "docs: typo").is_none());
+    assert!(max_message_bytes(10, "docs: typo\n").is_some());
+    // 'ä' takes two bytes in UTF-8
+    assert!(max_message_bytes(8, "docs: ä").is_none());
+    assert_eq!(
+        max_message_bytes(7, "docs: ä").unwrap().message,
+        "Message is 8 bytes long, but at most 7 are allowed"
+    );
+}
+
+#[test]
+fn test_reviewers() {
+    let allowed = vec![
+        "jane@example.com".to_string(),
+        "John Roe".to_string(),
+        "Max Mustermann <max@example.com>".to_string(),
+    ];
+    let footer = |token: &str, value: &str| vec![(token.to_string(), value.to_string())];
+    for value in [
+        "Jane Doe <jane@example.com>",
+        "john roe <john@elsewhere.org>",
+        "John Roe",
+        "Max Mustermann <max@example.com>",
+    ] {
+        assert!(
+            reviewers(&allowed, &footer("Reviewed-by", value)).is_empty(),
+            "Unexpected diagnostic for '{}'",
+            value
+        );
+    }
+    assert!(reviewers(&allowed, &footer("Refs", "Eve <eve@example.com>")).is_empty());
+
+    let diagnostics = reviewers(&allowed, &footer("Acked-by", "Eve <eve@example.com>"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "footer-reviewer");
+    assert_eq!(
+        diagnostics[0].message,
+        "Footer 'Acked-by: Eve <eve@example.com>' names a reviewer that is not in the list of reviewers"
+    );
+    assert_eq!(
+        reviewers(
+            &allowed,
+            &footer("Reviewed-by", "Max Mustermann <max@elsewhere.org>")
+        )
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn test_prefix_pattern() {
+    let pattern = Pattern::new(r"^(?:[A-Z]+-\d+|chore\(deps\))$").unwrap();
+    assert!(prefix_pattern(&pattern, "WEB-12", "").is_none());
+    assert!(prefix_pattern(&pattern, "chore", "deps").is_none());
+    assert_eq!(
+        prefix_pattern(&pattern, "chore", "ci").unwrap().message,
+        r"Prefix 'chore(ci)' does not match the pattern '^(?:[A-Z]+-\d+|chore\(deps\))$'"
+    );
+    assert!(prefix_pattern(&pattern, "feat", "").is_some());
+}
+
+#[test]
+fn test_co_author_email() {
+    let footers = crate::footer::parse(
+        "Co-authored-by: Jane Doe <jane@example.com>\nco-authored-by: John <john@mail.example.org>\nRefs: #1",
+    );
+    assert!(co_author_email(&footers).is_empty());
+    for value in [
+        "Jane Doe",
+        "Jane Doe jane@example.com",
+        "<jane@example.com>",
+        "Jane <jane>",
+        "Jane <jane@localhost>",
+        "Jane <jane@example.com",
+    ] {
+        let footers = vec![("Co-authored-by".to_string(), value.to_string())];
+        assert_eq!(
+            co_author_email(&footers).len(),
+            1,
+            "Should have warned for '{}'",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_forbidden_body_words() {
+    let words = vec!["DO NOT MERGE".to_string(), "console.log".to_string()];
+    assert!(forbidden_body_words(&words, "Adds the endpoint.\n\nRefs: #1").is_empty());
+    let diagnostics = forbidden_body_words(&words, "Adds the endpoint.\n\ndo not merge yet");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Line 3 of the body contains the forbidden word 'DO NOT MERGE'"
+    );
+}
+
+#[test]
+fn test_body_sections() {
+    let sections = vec!["Why".to_string(), "What".to_string()];
+    assert!(body_sections(&sections, "## Why\nIt broke.\n\n## What\nA fix.").is_empty());
+    assert!(body_sections(&sections, "why: it broke\n\nWHAT:\na fix").is_empty());
+    assert!(body_sections(&[], "").is_empty());
+
+    let diagnostics = body_sections(&sections, "### Why\nIt broke.");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Body is missing the section 'What', add it as '## What' or 'What:'"
+    );
+    assert_eq!(body_sections(&sections, "It broke, so fix it.").len(), 2);
+    assert_eq!(body_sections(&sections, "").len(), 2);
+}
+
+#[test]
+fn test_body_duplicates_subject() {
+    for body in [
+        "add the endpoint",
+        "Add  the endpoint.",
+        "\n  add the endpoint\n\nDetails",
+    ] {
+        assert_eq!(
+            body_duplicates_subject("add the endpoint", body)
+                .unwrap()
+                .code,
+            "body-duplicates-subject",
+            "Should have warned for '{}'",
+            body
+        );
+    }
+    for body in [
+        "",
+        "Add the endpoint for the users, so the app can list them.",
+        "Details\n\nadd the endpoint",
+    ] {
+        assert!(
+            body_duplicates_subject("add the endpoint", body).is_none(),
+            "Should not have warned for '{}'",
+            body
+        );
+    }
+    assert!(body_duplicates_subject("", "...").is_none());
+}
+
+#[test]
+fn test_issue_footer_style() {
+    let test_cases = vec![
+        (IssueFooterStyle::Hash, "Closes #12", true),
+        (IssueFooterStyle::Hash, "fixes #3\nRefs #4", true),
+        (IssueFooterStyle::Hash, "Closes: #12", false),
+        (IssueFooterStyle::ColonHash, "Closes: #12", true),
+        (IssueFooterStyle::ColonHash, "Closes #12", false),
+        (IssueFooterStyle::ColonHash, "Closes: JIRA-3", false),
+        (IssueFooterStyle::Ticket, "Refs: JIRA-3", true),
+        (IssueFooterStyle::Ticket, "Refs: #3", false),
+        (IssueFooterStyle::Ticket, "Reviewed-by: Jane", true),
+    ];
+    for (style, footers, valid) in test_cases {
+        let body = format!("Some details.\n\n{}", footers);
+        assert_eq!(
+            issue_footer_style(style, &body).is_empty(),
+            valid,
+            "Unexpected result for '{}' with {:?}",
+            footers,
+            style
+        );
+    }
+    assert!(
+        issue_footer_style(IssueFooterStyle::Hash, "Closes: #12\n\nReviewed-by: Jane").is_empty()
+    );
+    assert_eq!(
+        issue_footer_style(IssueFooterStyle::Hash, "Closes: #12")[0].message,
+        "Footer 'Closes: #12' has to be written like 'Closes #12'"
+    );
+}
+
+#[test]
+fn test_revert_sha() {
+    let sha = "1f0e3f4b8a2c9d7e6b5a4c3d2e1f0a9b8c7d6e5f";
+    assert!(revert_sha("revert", &format!("This reverts commit {}.", sha)).is_none());
+    assert!(revert_sha(
+        "Revert",
+        &format!("The cache broke the login.\n\nThis reverts commit {}", sha)
+    )
+    .is_none());
+    assert!(revert_sha("fix", "").is_none());
+    assert_eq!(
+        revert_sha("revert", "The cache broke the login.")
+            .unwrap()
+            .code,
+        "revert-sha"
+    );
+    assert_eq!(
+        revert_sha("revert", "This reverts commit 1f0e3f4.")
+            .unwrap()
+            .message,
+        "'This reverts commit 1f0e3f4.' should name the full 40 character hash of the reverted commit"
+    );
+}
+
+#[test]
+fn test_body_mentions_scope() {
+    assert!(body_mentions_scope("api", "The API returns the users now.").is_none());
+    assert!(body_mentions_scope("api,ui", "Shows the users in the UI.").is_none());
+    assert!(body_mentions_scope("", "No scope to mention.").is_none());
+    assert_eq!(
+        body_mentions_scope("api", "Returns the users now.")
+            .unwrap()
+            .message,
+        "Body doesn't mention the scope 'api'"
+    );
+    assert!(body_mentions_scope("api", "").is_some());
+}
+
+#[test]
+fn test_footer_blank_line() {
+    for body in [
+        "Some details.\n\nCloses: #12",
+        "Some details.\n\nCloses: #12\nReviewed-by: Jane",
+        "Closes: #12",
+        "Some details.",
+        "",
+    ] {
+        assert!(
+            footer_blank_line(body).is_none(),
+            "Should not have warned for '{}'",
+            body
+        );
+    }
+    assert_eq!(
+        footer_blank_line("Some details.\nCloses: #12\nReviewed-by: Jane")
+            .unwrap()
+            .message,
+        "Footer 'Closes: #12' has to be separated from the body by a blank line"
+    );
+}
+
+#[test]
+fn test_empty_body() {
+    assert!(empty_body("docs: typo").is_none());
+    assert!(empty_body("docs: typo\n").is_none());
+    assert!(empty_body("docs: typo\n\nFixes it").is_none());
+    assert_eq!(empty_body("docs: typo\n\n").unwrap().code, "body-empty");
+    assert_eq!(
+        empty_body("docs: typo\n \n\t\n").unwrap().code,
+        "body-empty"
+    );
+}
@@ -0,0 +1,58 @@
+use crate::analysis::AnalysisResult;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub skipped: usize,
+    pub types: Vec<(String, usize)>,
+    pub categories: Vec<(String, usize)>,
+}
+
+fn count(counts: &mut Vec<(String, usize)>, key: &str) {
+    match counts.iter_mut().find(|(existing, _)| existing == key) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((key.to_string(), 1)),
+    }
+}
+
+pub fn collect(reports: &[AnalysisResult]) -> Stats {
+    let mut stats = Stats {
+        total: reports.len(),
+        ..Stats::default()
+    };
+    for report in reports {
+        if report.skipped.is_some() {
+            stats.skipped += 1;
+        } else if report.valid() {
+            stats.valid += 1;
+        } else {
+            stats.invalid += 1;
+        }
+        if !report.commit.commit_type.is_empty() {
+            count(&mut stats.types, &report.commit.commit_type);
+        }
+        if let Some(category) = &report.category {
+            count(&mut stats.categories, category);
+        }
+    }
+    stats
+}
+
+// The types in the order they are listed in, followed by the types that
+// aren't listed, alphabetically.
+fn order_key(order: &[String], commit_type: &str) -> (usize, String) {
+    match order.iter().position(|listed| listed == commit_type) {
+        Some(index) => (index, String::new()),
+        None => (order.len(), commit_type.to_string()),
+    }
+}
+
+/// Sorts items by their commit type in the given order. Without an order,
+/// the items keep the order they are in.
+pub fn sort_by_type<T, F: Fn(&T) -> &str>(items: &mut [T], order: &[String], commit_type: F) {
+    if !order.is_empty() {
+        items.sort_by_key(|item| order_key(order, commit_type(item)));
+    }
+}
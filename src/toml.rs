@@ -0,0 +1,137 @@
+use crate::json::Value;
+
+// TOML has no null, so null values are left out, just like a missing key.
+
+fn key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return name.to_string();
+    }
+    // Basic strings are escaped like JSON strings.
+    Value::from(name).to_string()
+}
+
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Array(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .filter(|value| **value != Value::Null)
+                .map(inline)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        Value::Object(entries) => format!(
+            "{{ {} }}",
+            entries
+                .iter()
+                .filter(|(_, value)| *value != Value::Null)
+                .map(|(name, value)| format!("{} = {}", key(name), inline(value)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        value => value.to_string(),
+    }
+}
+
+fn is_table_array(value: &Value) -> bool {
+    match value {
+        Value::Array(values) => {
+            !values.is_empty() && values.iter().all(|value| matches!(value, Value::Object(_)))
+        }
+        _ => false,
+    }
+}
+
+// Plain keys have to come before the tables of an object, as every key after
+// a table header belongs to that table.
+fn write_table(output: &mut String, path: &str, entries: &[(String, Value)]) {
+    for (name, value) in entries {
+        match value {
+            Value::Null | Value::Object(_) => {}
+            value if is_table_array(value) => {}
+            value => output.push_str(&format!("{} = {}\n", key(name), inline(value))),
+        }
+    }
+    for (name, value) in entries {
+        let nested = match path {
+            "" => key(name),
+            path => format!("{}.{}", path, key(name)),
+        };
+        match value {
+            Value::Object(nested_entries) => {
+                output.push_str(&format!("\n[{}]\n", nested));
+                write_table(output, &nested, nested_entries);
+            }
+            Value::Array(values) if is_table_array(value) => {
+                for value in values {
+                    if let Value::Object(nested_entries) = value {
+                        output.push_str(&format!("\n[[{}]]\n", nested));
+                        write_table(output, &nested, nested_entries);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Serializes an object as a TOML document.
+pub(crate) fn to_string(entries: &[(String, Value)]) -> String {
+    let mut output = String::new();
+    write_table(&mut output, "", entries);
+    output
+}
+
+#[cfg(test)]
+use crate::json::object;
+
+#[test]
+fn test_to_string() {
+    let value = object(vec![
+        ("type", Value::from("feat")),
+        ("skipped", Value::Null),
+        ("count", Value::from(2)),
+        (
+            "footers",
+            object(vec![(
+                "BREAKING CHANGE",
+                Value::Array(vec![Value::from("a \"quoted\"\nline")]),
+            )]),
+        ),
+        ("empty", Value::Array(vec![])),
+        (
+            "diagnostics",
+            Value::Array(vec![
+                object(vec![("rule", Value::from("a"))]),
+                object(vec![("rule", Value::from("b"))]),
+            ]),
+        ),
+        ("valid", Value::from(true)),
+    ]);
+    let entries = match value {
+        Value::Object(entries) => entries,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        to_string(&entries),
+        r#"type = "feat"
+count = 2
+empty = []
+valid = true
+
+[footers]
+"BREAKING CHANGE" = ["a \"quoted\"\nline"]
+
+[[diagnostics]]
+rule = "a"
+
+[[diagnostics]]
+rule = "b"
+"#
+    );
+}